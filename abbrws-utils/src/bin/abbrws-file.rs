@@ -1,3 +1,5 @@
+use abbrws_utils::format::Format;
+use serde::Serialize;
 use structopt::StructOpt;
 use structopt::clap::AppSettings;
 use structopt::clap::ArgGroup;
@@ -25,6 +27,11 @@ struct Options {
 	#[structopt(default_value = "robotics")]
 	password: String,
 
+	/// The output format for --list: plain, json or csv.
+	#[structopt(long)]
+	#[structopt(default_value = "plain")]
+	format: Format,
+
 	/// List the contents of a directory.
 	#[structopt(long)]
 	#[structopt(group = "command")]
@@ -35,6 +42,40 @@ struct Options {
 	#[structopt(group = "command")]
 	create_dir: Option<String>,
 
+	/// Delete a file.
+	#[structopt(long)]
+	#[structopt(value_name = "PATH")]
+	#[structopt(group = "command")]
+	remove: Option<String>,
+
+	/// Delete a directory.
+	#[structopt(long)]
+	#[structopt(value_name = "PATH")]
+	#[structopt(group = "command")]
+	remove_dir: Option<String>,
+
+	/// Delete the contents of the directory too.
+	#[structopt(long)]
+	#[structopt(requires = "remove-dir")]
+	recursive: bool,
+
+	/// Rename or move a file or directory.
+	#[structopt(long)]
+	#[structopt(value_names = &["SOURCE", "DEST"])]
+	#[structopt(group = "command")]
+	rename: Option<Vec<String>>,
+
+	/// Copy a file or directory.
+	#[structopt(long)]
+	#[structopt(value_names = &["SOURCE", "DEST"])]
+	#[structopt(group = "command")]
+	copy: Option<Vec<String>>,
+
+	/// Overwrite the destination of a copy if it exists.
+	#[structopt(long)]
+	#[structopt(requires = "copy")]
+	overwrite: bool,
+
 	/// Download a file.
 	#[structopt(long)]
 	#[structopt(value_names = &["SOURCE", "DEST"])]
@@ -48,6 +89,37 @@ struct Options {
 	#[structopt(requires = "content-type")]
 	upload: Option<Vec<String>>,
 
+	/// Synchronize a local directory tree to the controller.
+	#[structopt(long)]
+	#[structopt(value_names = &["LOCAL", "REMOTE"])]
+	#[structopt(group = "command")]
+	sync: Option<Vec<String>>,
+
+	/// Synchronize from the controller to the local directory instead.
+	#[structopt(long)]
+	#[structopt(requires = "sync")]
+	pull: bool,
+
+	/// Delete files at the destination that do not exist at the source.
+	#[structopt(long)]
+	#[structopt(requires = "sync")]
+	delete: bool,
+
+	/// The UTC offset of the controller clock in minutes, like 60 for UTC+1.
+	///
+	/// The controller does not report its time zone.
+	/// Without this option, files are compared by size only, so a changed file with the same size is not synchronized.
+	#[structopt(long)]
+	#[structopt(value_name = "MINUTES")]
+	#[structopt(requires = "sync")]
+	#[structopt(allow_hyphen_values = true)]
+	utc_offset: Option<i64>,
+
+	/// Only print what would be synchronized.
+	#[structopt(long)]
+	#[structopt(requires = "sync")]
+	dry_run: bool,
+
 	/// The content-type of the uploaded file.
 	#[structopt(long)]
 	#[structopt(value_name = "MIME")]
@@ -76,36 +148,145 @@ async fn do_main(options: &Options) -> Result<(), String> {
 	if let Some(directory) = &options.list {
 		let mut client = connect()?;
 		let entries = client.list_files(directory).await.map_err(|e| format!("failed to retrieve directory contents: {}", e))?;
-		println!("{:#?}", entries);
+		print_entries(&entries, options.format)?;
 	} else if let Some(directory) = &options.create_dir {
 		let mut client = connect()?;
 		client.create_directory(directory).await.map_err(|e| format!("failed to create directory: {}", e))?;
+	} else if let Some(path) = &options.remove {
+		let mut client = connect()?;
+		client.delete_file(path).await.map_err(|e| format!("failed to delete file: {}", e))?;
+	} else if let Some(directory) = &options.remove_dir {
+		let mut client = connect()?;
+		client.delete_directory(directory, options.recursive).await.map_err(|e| format!("failed to delete directory: {}", e))?;
+	} else if let Some(paths) = &options.rename {
+		let mut client = connect()?;
+		client.rename(&paths[0], &paths[1]).await.map_err(|e| format!("failed to rename: {}", e))?;
+	} else if let Some(paths) = &options.copy {
+		let mut client = connect()?;
+		client.copy(&paths[0], &paths[1], options.overwrite).await.map_err(|e| format!("failed to copy: {}", e))?;
 	} else if let Some(paths) = &options.download {
 		let source = &paths[0];
 		let destination = &paths[1];
 		let mut client = connect()?;
-		let (content_type, data) = client.download_file(source).await.map_err(|e| format!("failed to download file: {}", e))?;
+		let show_progress = stderr_is_tty();
+		let content_type = client.download_file_to_path(source, destination, |progress| {
+			if show_progress {
+				print_progress(progress);
+			}
+		}).await.map_err(|e| format!("failed to download file: {}", e))?;
+		if show_progress {
+			eprintln!();
+		}
 		eprintln!("Content-Type: {}", content_type);
-		write_file(destination, data)?;
 	} else if let Some(paths) = &options.upload {
 		let source = &paths[0];
 		let destination = &paths[1];
-		let data = read_file(source)?;
 		let mut client = connect()?;
-		client.upload_file(destination, options.content_type.clone().unwrap(), data).await.map_err(|e| format!("failed to upload file: {}", e))?;
+		let show_progress = stderr_is_tty();
+		client.upload_file_from_path(source, destination, options.content_type.clone().unwrap(), |progress| {
+			if show_progress {
+				print_progress(progress);
+			}
+		}).await.map_err(|e| format!("failed to upload file {:?}: {}", source, e))?;
+		if show_progress {
+			eprintln!();
+		}
+	} else if let Some(paths) = &options.sync {
+		let local = &paths[0];
+		let remote = &paths[1];
+		let direction = if options.pull { abbrws::SyncDirection::Download } else { abbrws::SyncDirection::Upload };
+		let mut client = connect()?;
+		let sync_options = abbrws::SyncOptions {
+			delete: options.delete,
+			utc_offset_s: options.utc_offset.map(|x| x * 60),
+		};
+		let plan = client.plan_sync(local, remote, direction, &sync_options).await.map_err(|e| format!("failed to compare directories: {}", e))?;
+		print!("{}", plan);
+		if plan.is_empty() {
+			eprintln!("{} and {} are in sync", local, remote);
+		} else if !options.dry_run {
+			client.apply_sync(&plan, |_| ()).await.map_err(|e| format!("failed to synchronize: {}", e))?;
+		}
 	}
 
 	Ok(())
 }
 
-fn read_file(path: impl AsRef<std::path::Path>) -> Result<Vec<u8>, String> {
-	let path = path.as_ref();
-	std::fs::read(path).map_err(|e| format!("failed to read from file {:?}: {}", path, e))
+/// A directory entry in the form used for CSV output.
+///
+/// Fields that do not apply to an entry type are left empty.
+#[derive(Serialize)]
+struct DirEntryRow<'a> {
+	#[serde(rename = "type")]
+	kind: &'static str,
+	name: &'a str,
+	size: Option<usize>,
+	cdate: Option<&'a str>,
+	mdate: Option<&'a str>,
+	read_only: Option<bool>,
+}
+
+impl<'a> DirEntryRow<'a> {
+	fn new(entry: &'a abbrws::DirEntry) -> Self {
+		let row = Self { kind: "", name: entry.name(), size: None, cdate: None, mdate: None, read_only: None };
+		match entry {
+			abbrws::DirEntry::Directory(_) => Self { kind: "directory", ..row },
+			abbrws::DirEntry::File(file) => Self {
+				kind: "file",
+				size: Some(file.size),
+				cdate: Some(&file.cdate),
+				mdate: Some(&file.mdate),
+				read_only: Some(file.read_only),
+				..row
+			},
+			abbrws::DirEntry::Device(device) => Self {
+				kind: "device",
+				read_only: Some(device.read_only),
+				..row
+			},
+		}
+	}
 }
 
-fn write_file(path: impl AsRef<std::path::Path>, data: impl AsRef<[u8]>) -> Result<(), String> {
-	let path = path.as_ref();
-	std::fs::write(path, data).map_err(|e| format!("failed to write to file {:?}: {}", path, e))
+fn print_entries(entries: &[abbrws::DirEntry], format: Format) -> Result<(), String> {
+	match format {
+		Format::Json => abbrws_utils::format::print_json(entries),
+		Format::Csv => abbrws_utils::format::print_csv(entries.iter().map(DirEntryRow::new)),
+		Format::Plain => {
+			for entry in entries {
+				match entry {
+					abbrws::DirEntry::Directory(directory) => {
+						println!("{}/", Paint::blue(&directory.name));
+					},
+					abbrws::DirEntry::File(file) => {
+						println!("{name}  {size} bytes, modified {mdate}{read_only}",
+							name = file.name,
+							size = file.size,
+							mdate = file.mdate,
+							read_only = if file.read_only { ", read-only" } else { "" },
+						);
+					},
+					abbrws::DirEntry::Device(device) => {
+						println!("{name}  ({kind}, {free} of {total} bytes free)",
+							name = Paint::magenta(&device.name),
+							kind = device.device_type,
+							free = device.free_space,
+							total = device.total_space,
+						);
+					},
+				}
+			}
+			Ok(())
+		},
+	}
+}
+
+/// Print the progress of a transfer on a single line of stderr.
+fn print_progress(progress: abbrws::Progress) {
+	match progress.fraction() {
+		Some(fraction) => eprint!("\r{} of {} bytes ({:.0}%)", progress.transferred, progress.total.unwrap_or(0), fraction * 100.0),
+		None => eprint!("\r{} bytes", progress.transferred),
+	}
 }
 
 extern "C" {
@@ -116,6 +297,10 @@ fn stdout_is_tty() -> bool {
 	unsafe { isatty(1) != 0 }
 }
 
+fn stderr_is_tty() -> bool {
+	unsafe { isatty(2) != 0 }
+}
+
 #[allow(clippy::let_and_return)]
 fn should_color() -> bool {
 	// CLICOLOR not set? Check if stdout is a TTY.
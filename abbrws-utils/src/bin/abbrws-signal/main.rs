@@ -0,0 +1,259 @@
+use abbrws_utils::format::Format;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use yansi::Paint;
+
+mod record;
+mod watch;
+
+#[derive(StructOpt)]
+#[structopt(setting(structopt::clap::AppSettings::DeriveDisplayOrder))]
+#[structopt(setting(structopt::clap::AppSettings::ColoredHelp))]
+#[structopt(setting(structopt::clap::AppSettings::UnifiedHelpMessage))]
+#[structopt(group(structopt::clap::ArgGroup::with_name("selection").required(true)))]
+struct Options {
+	/// The host to connect to.
+	#[structopt(long, short)]
+	#[structopt(required_unless = "replay")]
+	host: Option<String>,
+
+	/// The user to authenticate as.
+	#[structopt(long, short)]
+	#[structopt(default_value = "Default User")]
+	user: String,
+
+	/// The password for the user.
+	#[structopt(long, short)]
+	#[structopt(default_value = "robotics")]
+	password: String,
+
+	/// The output format: plain, json or csv.
+	///
+	/// With --watch, one record is printed per poll.
+	/// Recordings made with --record are always CSV files.
+	#[structopt(long)]
+	#[structopt(default_value = "plain")]
+	format: Format,
+
+	/// List all available signals.
+	#[structopt(long)]
+	#[structopt(group = "selection")]
+	list: bool,
+
+	/// Show or set one signal.
+	#[structopt(long)]
+	#[structopt(group = "selection")]
+	signal: Option<String>,
+
+	/// Set the value of a signal.
+	#[structopt(long)]
+	#[structopt(requires = "signal")]
+	set: Option<abbrws::SignalValue>,
+
+	/// Record changes of the signals given with --signals to a CSV file.
+	#[structopt(long)]
+	#[structopt(value_name = "FILE")]
+	#[structopt(group = "selection")]
+	#[structopt(requires = "signals")]
+	record: Option<PathBuf>,
+
+	/// Show a live table of the signals given with --signals or --match.
+	#[structopt(long)]
+	#[structopt(group = "selection")]
+	watch: bool,
+
+	/// The signals to record or watch, separated by commas.
+	#[structopt(long)]
+	#[structopt(use_delimiter = true)]
+	signals: Vec<String>,
+
+	/// Watch all signals with a title matching a glob pattern, like 'Local/DRV_1/*'.
+	#[structopt(long = "match")]
+	#[structopt(value_name = "GLOB")]
+	#[structopt(requires = "watch")]
+	#[structopt(conflicts_with = "signals")]
+	match_: Option<String>,
+
+	/// Stop watching once a signal has the given value, like 'Local/DRV_1/DRV1K1=1'.
+	#[structopt(long)]
+	#[structopt(value_name = "SIGNAL=VALUE")]
+	#[structopt(requires = "watch")]
+	until: Option<watch::Condition>,
+
+	/// The polling interval in milliseconds for --watch.
+	#[structopt(long)]
+	#[structopt(value_name = "MS")]
+	#[structopt(default_value = "100")]
+	interval: u64,
+
+	/// Stop recording after this many seconds.
+	#[structopt(long)]
+	#[structopt(value_name = "SECONDS")]
+	#[structopt(requires = "record")]
+	duration: Option<f64>,
+
+	/// Print a recording made with --record.
+	#[structopt(long)]
+	#[structopt(value_name = "FILE")]
+	#[structopt(group = "selection")]
+	replay: Option<PathBuf>,
+
+	/// Compare the recording given with --replay against another recording.
+	#[structopt(long)]
+	#[structopt(value_name = "FILE")]
+	#[structopt(requires = "replay")]
+	diff: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+	if let Err(e) = do_main(&Options::from_args()).await {
+		eprintln!("{} {}", Paint::red("Error:").bold(), e);
+		std::process::exit(1);
+	}
+}
+
+async fn do_main(options: &Options) -> Result<(), String> {
+	if !should_color() {
+		Paint::disable();
+	}
+
+	if let Some(path) = &options.replay {
+		return match &options.diff {
+			Some(other) => record::diff(path, other, options.format),
+			None => record::replay(path, options.format),
+		};
+	}
+
+	let host = options.host.as_deref().ok_or("no --host specified")?;
+	let mut client = abbrws::Client::new(host, &options.user, &options.password)
+		.map_err(|e| format!("failed to connect to {:?}: {}", host, e))?;
+
+	if let Some(path) = &options.record {
+		let duration = options.duration.map(std::time::Duration::from_secs_f64);
+		record::record(&mut client, path, &options.signals, duration).await
+	} else if options.watch {
+		let selection = match &options.match_ {
+			Some(pattern) => watch::Selection::Glob(pattern),
+			None if !options.signals.is_empty() => watch::Selection::Names(&options.signals),
+			None => return Err(String::from("--watch needs --signals or --match")),
+		};
+		let interval = std::time::Duration::from_millis(options.interval);
+		watch::watch(&mut client, selection, interval, options.until.as_ref(), options.format).await
+	} else if options.list {
+		list_signals(&mut client, options.format).await
+	} else if let Some(signal) = &options.signal {
+		if let Some(value) = options.set {
+			set_signal(&mut client, signal, value).await?;
+			show_signal(&mut client, signal, options.format).await
+		} else {
+			show_signal(&mut client, signal, options.format).await
+		}
+	} else {
+		Err(String::from("no --list, --signal, --watch, --record or --replay specified."))
+	}
+}
+
+async fn list_signals(client: &mut abbrws::Client, format: Format) -> Result<(), String> {
+	let signals = client.get_signals().await
+		.map_err(|e| format!("failed to retrieve signals: {}", e))?;
+
+	match format {
+		Format::Json => return abbrws_utils::format::print_json(&signals),
+		Format::Csv => return abbrws_utils::format::print_csv(&signals),
+		Format::Plain => (),
+	}
+
+	let title_width = signals.iter().map(|x| x.title.len()).max().unwrap_or(0);
+
+	for signal in signals {
+		let simulated = match signal.lstate {
+			abbrws::SimulationState::Simulated => Paint::red(" [simulated]").to_string(),
+			abbrws::SimulationState::NotSimulated => String::new(),
+		};
+		println!("{title:<title_width$} = {value:<10} ({kind}){simulated}",
+			title       = Paint::blue(signal.title),
+			title_width = title_width,
+			kind        = Paint::magenta(signal.kind),
+			value       = Paint::yellow(format!("{}", signal.lvalue)),
+			simulated   = simulated,
+		);
+	}
+	Ok(())
+}
+
+async fn show_signal(client: &mut abbrws::Client, signal: &str, format: Format) -> Result<(),  String> {
+	let signal = client.get_signal(signal).await
+		.map_err(|e| format!("failed to retrieve signal {:?}: {}", signal, e))?;
+
+	match format {
+		Format::Json => return abbrws_utils::format::print_json(&signal),
+		Format::Csv => return abbrws_utils::format::print_csv([&signal]),
+		Format::Plain => (),
+	}
+	println!("{title} = {value} ({kind})",
+		title = Paint::blue(&signal.title),
+		kind  = Paint::magenta(signal.kind),
+		value = Paint::yellow(signal.lvalue),
+	);
+	println!("  logical state:  {} (changed at {})", signal.lstate, format_time(signal.ltime));
+	match signal.pvalue {
+		Some(pvalue) => println!("  physical value: {} ({}, changed at {})", Paint::yellow(pvalue), signal.phstate, format_time(signal.ptime)),
+		None => println!("  physical value: unknown"),
+	}
+	println!("  device:         {}", signal.unit);
+	println!("  category:       {}", signal.category);
+	match signal.quality {
+		Some(quality) => println!("  quality:        {}", quality),
+		None => println!("  quality:        unknown"),
+	}
+	Ok(())
+}
+
+/// Format a timestamp as seconds since the Unix epoch.
+fn format_time(time: Option<std::time::SystemTime>) -> String {
+	match time.map(|x| x.duration_since(std::time::UNIX_EPOCH)) {
+		Some(Ok(x)) => format!("{}.{:06}", x.as_secs(), x.subsec_micros()),
+		_ => String::from("unknown"),
+	}
+}
+
+async fn set_signal(client: &mut abbrws::Client, signal: &str, value: abbrws::SignalValue) -> Result<(), String> {
+	client.set_signal(signal, value).await
+		.map_err(|e| format!("failed to set signal {:?} to {}: {}", signal, value, e))?;
+	Ok(())
+}
+
+extern "C" {
+	fn isatty(fd: std::os::raw::c_int) -> std::os::raw::c_int;
+}
+
+fn stdout_is_tty() -> bool {
+	unsafe { isatty(1) != 0 }
+}
+
+#[allow(clippy::let_and_return)]
+fn should_color() -> bool {
+	// CLICOLOR not set? Check if stdout is a TTY.
+	let clicolor = match std::env::var_os("CLICOLOR") {
+		Some(x) => x,
+		None => return stdout_is_tty(),
+	};
+
+	// CLICOLOR not ascii? Disable colors.
+	let clicolor = match clicolor.to_str() {
+		Some(x) => x,
+		None => return false,
+	};
+
+	if clicolor.eq_ignore_ascii_case("auto") {
+		stdout_is_tty()
+	} else {
+		let force = false;
+		let force = force || clicolor.eq_ignore_ascii_case("yes");
+		let force = force || clicolor.eq_ignore_ascii_case("true");
+		let force = force || clicolor.eq_ignore_ascii_case("always");
+		let force = force || clicolor.eq_ignore_ascii_case("1");
+		force
+	}
+}
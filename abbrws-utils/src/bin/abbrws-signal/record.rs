@@ -0,0 +1,295 @@
+//! Recording and replaying signal traces as CSV files.
+//!
+//! A recording has one row per value change, with the columns `time`, `signal` and `value`.
+//! The first rows hold the values at the start of the recording.
+//! The time is in seconds since the Unix epoch.
+//!
+//! For the first rows, the time is the `ltime` of the signal as reported by the controller,
+//! or the local time if the controller does not report it.
+//! Subscription events from the controller do not include a timestamp,
+//! so the other rows use the local time at which the change was received.
+
+use abbrws_utils::format::Format;
+use futures_util::StreamExt;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+use yansi::Paint;
+
+const HEADER: [&str; 3] = ["time", "signal", "value"];
+
+/// A single row of a recording.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Row {
+	pub time: String,
+	pub signal: String,
+	pub value: String,
+}
+
+impl Row {
+	/// Create a row for a signal value, using the local time if the time of the value is unknown.
+	fn new(signal: String, value: abbrws::SignalValue, time: Option<SystemTime>) -> Self {
+		Self {
+			time: super::format_time(Some(time.unwrap_or_else(SystemTime::now))),
+			signal,
+			value: value.to_string(),
+		}
+	}
+}
+
+/// Subscribe to the given signals and write every change to a CSV file.
+///
+/// Recording stops after `duration`, or runs until the process is killed if no duration is given.
+/// The file is flushed after each row, so an interrupted recording is still usable.
+///
+/// A subscription is used instead of polling, so that short pulses between two polls are not missed.
+pub async fn record(
+	client: &mut abbrws::Client,
+	path: &Path,
+	signals: &[String],
+	duration: Option<Duration>,
+) -> Result<(), String> {
+	let mut writer = create(path)?;
+	let deadline = duration.map(|x| tokio::time::Instant::now() + x);
+
+	// Subscribe before reading the initial values, so that no change can fall in between.
+	let mut subscription = client.subscribe_signals(signals).await
+		.map_err(|e| format!("failed to subscribe to signals: {}", e))?;
+
+	let mut last_values = BTreeMap::new();
+	let mut row_count = 0;
+	for name in signals {
+		let signal = client.get_signal(name).await
+			.map_err(|e| format!("failed to retrieve signal {:?}: {}", name, e))?;
+		last_values.insert(signal.title.clone(), signal.lvalue);
+		write_row(&mut writer, path, &Row::new(signal.title, signal.lvalue, signal.ltime))?;
+		row_count += 1;
+	}
+
+	loop {
+		let next = match deadline {
+			None => subscription.next().await,
+			Some(deadline) => match tokio::time::timeout_at(deadline, subscription.next()).await {
+				Ok(x) => x,
+				Err(_) => break,
+			},
+		};
+		let signal = match next {
+			Some(x) => x.map_err(|e| format!("signal subscription failed: {}", e))?,
+			None => return Err(String::from("signal subscription ended unexpectedly")),
+		};
+		if last_values.get(&signal.title) != Some(&signal.lvalue) {
+			last_values.insert(signal.title.clone(), signal.lvalue);
+			write_row(&mut writer, path, &Row::new(signal.title, signal.lvalue, None))?;
+			row_count += 1;
+		}
+	}
+
+	if let Err(e) = subscription.close().await {
+		eprintln!("{} failed to remove signal subscription: {}", Paint::yellow("Warning:").bold(), e);
+	}
+	eprintln!("recorded {} changes to {}", row_count, path.display());
+	Ok(())
+}
+
+/// Create a new recording and write the header.
+fn create(path: &Path) -> Result<csv::Writer<std::fs::File>, String> {
+	let mut writer = csv::WriterBuilder::new()
+		.has_headers(false)
+		.from_path(path)
+		.map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+	writer.write_record(HEADER)
+		.and_then(|()| writer.flush().map_err(Into::into))
+		.map_err(|e| format!("failed to write to {}: {}", path.display(), e))?;
+	Ok(writer)
+}
+
+/// Write a row to a recording and flush it to disk.
+fn write_row(writer: &mut csv::Writer<std::fs::File>, path: &Path, row: &Row) -> Result<(), String> {
+	writer.serialize(row)
+		.and_then(|()| writer.flush().map_err(Into::into))
+		.map_err(|e| format!("failed to write to {}: {}", path.display(), e))
+}
+
+/// Print the rows of a recording.
+pub fn replay(path: &Path, format: Format) -> Result<(), String> {
+	let rows = read(path)?;
+	match format {
+		Format::Json => return abbrws_utils::format::print_json(&rows),
+		Format::Csv => return abbrws_utils::format::print_csv(&rows),
+		Format::Plain => (),
+	}
+
+	let signal_width = rows.iter().map(|x| x.signal.len()).max().unwrap_or(0);
+	for row in rows {
+		println!("{time} {signal:<signal_width$} = {value}",
+			time         = row.time,
+			signal       = Paint::blue(row.signal),
+			signal_width = signal_width,
+			value        = Paint::yellow(row.value),
+		);
+	}
+	Ok(())
+}
+
+/// The first difference between the values of a signal in two recordings.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
+pub struct Difference {
+	pub signal: String,
+
+	/// The number of the first change that differs, starting at 1.
+	pub index: usize,
+
+	/// The value in the first recording, or `None` if it has no such change.
+	pub a: Option<String>,
+
+	/// The value in the second recording, or `None` if it has no such change.
+	pub b: Option<String>,
+}
+
+/// Compare the value sequences of two recordings, ignoring the timestamps.
+///
+/// Returns an error if the recordings differ, so the result can be used in scripts.
+pub fn diff(path_a: &Path, path_b: &Path, format: Format) -> Result<(), String> {
+	let differences = differences(read(path_a)?, read(path_b)?);
+	match format {
+		Format::Json => abbrws_utils::format::print_json(&differences)?,
+		Format::Csv => abbrws_utils::format::print_csv(&differences)?,
+		Format::Plain => {
+			for difference in &differences {
+				match (&difference.a, &difference.b) {
+					(Some(_), None) if difference.index == 1 => println!("{}: only in {}", Paint::blue(&difference.signal), path_a.display()),
+					(None, Some(_)) if difference.index == 1 => println!("{}: only in {}", Paint::blue(&difference.signal), path_b.display()),
+					(a, b) => println!("{}: change {} differs: {} vs {}",
+						Paint::blue(&difference.signal),
+						difference.index,
+						Paint::red(a.as_deref().unwrap_or("(none)")),
+						Paint::green(b.as_deref().unwrap_or("(none)")),
+					),
+				}
+			}
+		},
+	}
+
+	if differences.is_empty() {
+		Ok(())
+	} else {
+		Err(format!("recordings differ for {} signals", differences.len()))
+	}
+}
+
+/// Find the first difference for each signal in two recordings.
+fn differences(a: Vec<Row>, b: Vec<Row>) -> Vec<Difference> {
+	let a = values_per_signal(a);
+	let b = values_per_signal(b);
+	let empty = Vec::new();
+
+	let signals : std::collections::BTreeSet<_> = a.keys().chain(b.keys()).collect();
+	signals.into_iter().filter_map(|signal| {
+		let values_a = a.get(signal).unwrap_or(&empty);
+		let values_b = b.get(signal).unwrap_or(&empty);
+		let index = values_a.iter().zip(values_b).position(|(a, b)| a != b)
+			.or_else(|| if values_a.len() != values_b.len() { Some(values_a.len().min(values_b.len())) } else { None })?;
+		Some(Difference {
+			signal: signal.clone(),
+			index: index + 1,
+			a: values_a.get(index).cloned(),
+			b: values_b.get(index).cloned(),
+		})
+	}).collect()
+}
+
+fn values_per_signal(rows: Vec<Row>) -> BTreeMap<String, Vec<String>> {
+	let mut result = BTreeMap::<_, Vec<_>>::new();
+	for row in rows {
+		result.entry(row.signal).or_default().push(row.value);
+	}
+	result
+}
+
+/// Read all rows from a recording.
+fn read(path: &Path) -> Result<Vec<Row>, String> {
+	let mut reader = csv::Reader::from_path(path)
+		.map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+	let header = reader.headers()
+		.map_err(|e| format!("failed to read from {}: {}", path.display(), e))?;
+	if header != HEADER[..] {
+		return Err(format!("{} is not a signal recording: missing header", path.display()));
+	}
+
+	reader.deserialize()
+		.collect::<Result<_, _>>()
+		.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_write_read() {
+		let path = std::env::temp_dir().join(format!("abbrws-signal-record-test-{}.csv", std::process::id()));
+		let rows = [
+			Row { time: "1.000000".into(), signal: "Local/DRV_1/DO1".into(), value: "1".into() },
+			Row { time: "2.000000".into(), signal: "a,b".into(), value: r#"say "hi""#.into() },
+			Row { time: "3.000000".into(), signal: "line\nbreak".into(), value: "".into() },
+		];
+
+		let mut writer = create(&path).unwrap();
+		for row in &rows {
+			write_row(&mut writer, &path, row).unwrap();
+		}
+		drop(writer);
+
+		let written = std::fs::read_to_string(&path).unwrap();
+		let read_back = read(&path);
+		std::fs::remove_file(&path).unwrap();
+
+		assert!(written.starts_with("time,signal,value\n1.000000,Local/DRV_1/DO1,1\n2.000000,\"a,b\",\"say \"\"hi\"\"\"\n"));
+		assert!(read_back == Ok(rows.to_vec()));
+	}
+
+	#[test]
+	fn test_row_time() {
+		let ltime = SystemTime::UNIX_EPOCH + Duration::from_micros(1_500_000);
+		let row = Row::new("Local/DRV_1/DO1".into(), abbrws::SignalValue::Binary(true), Some(ltime));
+		assert!(row.time == "1.500000");
+		assert!(row.value == "1");
+
+		// Without a controller timestamp, the local clock is used.
+		let before = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs_f64();
+		let row = Row::new("Local/DRV_1/DO1".into(), abbrws::SignalValue::Binary(true), None);
+		let time : f64 = row.time.parse().unwrap();
+		assert!(time >= before.floor());
+	}
+
+	#[test]
+	fn test_differences() {
+		let row = |signal: &str, value: &str| Row { time: "1.000000".into(), signal: signal.into(), value: value.into() };
+		let a = vec![row("A", "0"), row("A", "1"), row("B", "0"), row("C", "5"), row("D", "1")];
+		let b = vec![row("A", "0"), row("B", "0"), row("B", "1"), row("C", "5"), row("E", "2")];
+		let difference = |signal: &str, index, a: Option<&str>, b: Option<&str>| Difference {
+			signal: signal.into(),
+			index,
+			a: a.map(String::from),
+			b: b.map(String::from),
+		};
+		assert!(differences(a, b) == [
+			difference("A", 2, Some("1"), None),
+			difference("B", 2, None, Some("1")),
+			difference("D", 1, Some("1"), None),
+			difference("E", 1, None, Some("2")),
+		]);
+	}
+
+	#[test]
+	fn test_read_without_header() {
+		let path = std::env::temp_dir().join(format!("abbrws-signal-record-test-header-{}.csv", std::process::id()));
+		std::fs::write(&path, "1.000000,Local/DRV_1/DO1,1\n").unwrap();
+		let result = read(&path);
+		std::fs::remove_file(&path).unwrap();
+		assert!(let Err(_) = result);
+	}
+}
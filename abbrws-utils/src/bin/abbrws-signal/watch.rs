@@ -0,0 +1,217 @@
+//! Live view of a set of signals.
+//!
+//! With `--format json` or `--format csv`, one record is printed per poll instead of a table.
+
+use abbrws_utils::format::Format;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use std::time::SystemTime;
+use yansi::Paint;
+
+/// Which signals to watch.
+pub enum Selection<'a> {
+	/// A fixed list of signals, polled one by one.
+	Names(&'a [String]),
+
+	/// All signals with a title that matches a glob pattern.
+	Glob(&'a str),
+}
+
+/// A condition to stop watching: a signal reaching a value.
+pub struct Condition {
+	pub signal: String,
+	pub value: String,
+}
+
+impl std::str::FromStr for Condition {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		let (signal, value) = input.split_once('=')
+			.ok_or_else(|| format!("invalid condition {:?}: expected SIGNAL=VALUE", input))?;
+		let value : abbrws::SignalValue = value.trim().parse()
+			.map_err(|e| format!("invalid condition {:?}: {}", input, e))?;
+		Ok(Self {
+			signal: signal.trim().to_string(),
+			value: value.to_string(),
+		})
+	}
+}
+
+/// The values of the watched signals at one poll.
+#[derive(serde::Serialize)]
+struct PollRecord<'a> {
+	time: String,
+	values: BTreeMap<&'a str, abbrws::SignalValue>,
+}
+
+/// Redraw a table of the selected signals every interval, highlighting changed values.
+///
+/// For JSON output, each poll is printed as a JSON object on a single line.
+/// For CSV output, each poll is printed as a row with a column per signal.
+///
+/// Returns when the `until` condition is met, or with an error if the connection fails.
+pub async fn watch(client: &mut abbrws::Client, selection: Selection<'_>, interval: Duration, until: Option<&Condition>, format: Format) -> Result<(), String> {
+	let mut previous = BTreeMap::new();
+	let mut csv = CsvRecords::default();
+	loop {
+		let signals = poll(client, &selection).await?;
+		if let Some(until) = until {
+			if !signals.iter().any(|x| x.title == until.signal) {
+				return Err(format!("signal {:?} from --until is not being watched", until.signal));
+			}
+		}
+
+		match format {
+			Format::Plain => print_table(&signals, &previous),
+			Format::Json => abbrws_utils::format::print_json_line(&PollRecord {
+				time: super::format_time(Some(SystemTime::now())),
+				values: signals.iter().map(|x| (x.title.as_str(), x.lvalue)).collect(),
+			})?,
+			Format::Csv => csv.write(&signals)?,
+		}
+
+		if let Some(until) = until {
+			let reached = signals.iter().any(|x| x.title == until.signal && x.lvalue.to_string() == until.value);
+			if reached {
+				return Ok(());
+			}
+		}
+
+		previous = signals.into_iter().map(|x| (x.title, x.lvalue)).collect();
+		tokio::time::sleep(interval).await;
+	}
+}
+
+/// Draw a table of signals, highlighting the values that changed since the previous poll.
+fn print_table(signals: &[abbrws::Signal], previous: &BTreeMap<String, abbrws::SignalValue>) {
+	// Move the cursor to the top left and clear the screen before drawing.
+	// When the output is not a terminal, separate the tables with an empty line instead.
+	if super::stdout_is_tty() {
+		print!("\x1b[H\x1b[J");
+	} else if !previous.is_empty() {
+		println!();
+	}
+	let title_width = signals.iter().map(|x| x.title.len()).max().unwrap_or(0);
+	for signal in signals {
+		let value = signal.lvalue.to_string();
+		let value = match previous.get(&signal.title) {
+			Some(old) if old != &signal.lvalue => Paint::green(value).bold(),
+			_ => Paint::yellow(value),
+		};
+		println!("{title:<title_width$} = {value:<10} ({kind})",
+			title       = Paint::blue(&signal.title),
+			title_width = title_width,
+			kind        = Paint::magenta(signal.kind),
+			value       = value,
+		);
+	}
+}
+
+/// CSV output for watch mode, with a `time` column and a column per signal.
+#[derive(Default)]
+struct CsvRecords {
+	/// The signal titles of the columns, taken from the first poll.
+	columns: Option<Vec<String>>,
+}
+
+impl CsvRecords {
+	/// Write the values of one poll as a row, preceded by the header for the first poll.
+	fn write(&mut self, signals: &[abbrws::Signal]) -> Result<(), String> {
+		let titles : Vec<_> = signals.iter().map(|x| x.title.clone()).collect();
+		let mut writer = csv::Writer::from_writer(std::io::stdout());
+		match &self.columns {
+			None => {
+				let header = std::iter::once("time").chain(titles.iter().map(String::as_str));
+				writer.write_record(header).map_err(|e| format!("failed to write CSV: {}", e))?;
+				self.columns = Some(titles);
+			},
+			Some(columns) if *columns != titles => return Err(String::from("the set of watched signals changed, which can not be written as CSV")),
+			Some(_) => (),
+		}
+
+		let time = super::format_time(Some(SystemTime::now()));
+		let values = signals.iter().map(|x| x.lvalue.to_string());
+		writer.write_record(std::iter::once(time).chain(values)).map_err(|e| format!("failed to write CSV: {}", e))?;
+		writer.flush().map_err(|e| format!("failed to write CSV: {}", e))
+	}
+}
+
+/// Get the current state of the selected signals.
+async fn poll(client: &mut abbrws::Client, selection: &Selection<'_>) -> Result<Vec<abbrws::Signal>, String> {
+	match selection {
+		Selection::Names(names) => {
+			let mut signals = Vec::with_capacity(names.len());
+			for name in names.iter() {
+				let signal = client.get_signal(name).await
+					.map_err(|e| format!("failed to retrieve signal {:?}: {}", name, e))?;
+				signals.push(signal.into());
+			}
+			Ok(signals)
+		},
+		Selection::Glob(pattern) => {
+			let signals = client.get_signals().await
+				.map_err(|e| format!("failed to retrieve signals: {}", e))?;
+			Ok(signals.into_iter().filter(|x| glob_match(pattern, &x.title)).collect())
+		},
+	}
+}
+
+/// Check if a text matches a glob pattern with `*` and `?` wildcards.
+fn glob_match(pattern: &str, text: &str) -> bool {
+	let pattern : Vec<char> = pattern.chars().collect();
+	let text : Vec<char> = text.chars().collect();
+
+	// Position after the last `*`, and the text position it is currently matched up to.
+	let mut backtrack = None;
+	let (mut p, mut t) = (0, 0);
+	while t < text.len() {
+		match pattern.get(p) {
+			Some('*') => {
+				backtrack = Some((p + 1, t));
+				p += 1;
+			},
+			Some(&c) if c == '?' || c == text[t] => {
+				p += 1;
+				t += 1;
+			},
+			_ => match backtrack {
+				Some((star_p, star_t)) => {
+					backtrack = Some((star_p, star_t + 1));
+					p = star_p;
+					t = star_t + 1;
+				},
+				None => return false,
+			},
+		}
+	}
+	pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::check;
+
+	#[test]
+	fn test_glob_match() {
+		check!(glob_match("Local/DRV_1/DO1", "Local/DRV_1/DO1"));
+		check!(!glob_match("Local/DRV_1/DO1", "Local/DRV_1/DO2"));
+
+		check!(glob_match("Local/DRV_1/*", "Local/DRV_1/DO1"));
+		check!(glob_match("Local/DRV_1/*", "Local/DRV_1/"));
+		check!(!glob_match("Local/DRV_1/*", "Local/PANEL/SS1"));
+		check!(glob_match("*/DO*", "Local/DRV_1/DO1"));
+		check!(glob_match("*1", "Local/DRV_1/DO1"));
+		check!(!glob_match("*1", "Local/DRV_1/DO2"));
+
+		check!(glob_match("Local/DRV_1/DO?", "Local/DRV_1/DO1"));
+		check!(!glob_match("Local/DRV_1/DO?", "Local/DRV_1/DO"));
+		check!(!glob_match("Local/DRV_1/DO?", "Local/DRV_1/DO10"));
+
+		check!(glob_match("", ""));
+		check!(!glob_match("", "Local/DRV_1/DO1"));
+		check!(glob_match("*", ""));
+		check!(glob_match("**", "Local/DRV_1/DO1"));
+	}
+}
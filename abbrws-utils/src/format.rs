@@ -0,0 +1,59 @@
+//! Machine-readable output for the command line tools.
+
+use serde::Serialize;
+
+/// The output format of a command.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+	/// Human-readable text, possibly with colors.
+	Plain,
+
+	/// A JSON document.
+	Json,
+
+	/// CSV with a header row.
+	Csv,
+}
+
+impl std::str::FromStr for Format {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match input {
+			"plain" => Ok(Self::Plain),
+			"json" => Ok(Self::Json),
+			"csv" => Ok(Self::Csv),
+			_ => Err(format!("unknown format {:?}, expected json, csv or plain", input)),
+		}
+	}
+}
+
+/// Write a value as pretty-printed JSON to standard output.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+	let stdout = std::io::stdout();
+	let mut stdout = stdout.lock();
+	serde_json::to_writer_pretty(&mut stdout, value)
+		.map_err(|e| format!("failed to write JSON: {}", e))?;
+	println!();
+	Ok(())
+}
+
+/// Write a value as compact JSON on a single line to standard output.
+///
+/// This allows a stream of values to be processed line by line.
+pub fn print_json_line<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+	let line = serde_json::to_string(value)
+		.map_err(|e| format!("failed to write JSON: {}", e))?;
+	println!("{}", line);
+	Ok(())
+}
+
+/// Write rows as CSV with a header to standard output.
+pub fn print_csv<T: Serialize>(rows: impl IntoIterator<Item = T>) -> Result<(), String> {
+	let stdout = std::io::stdout();
+	let mut writer = csv::Writer::from_writer(stdout.lock());
+	for row in rows {
+		writer.serialize(row).map_err(|e| format!("failed to write CSV: {}", e))?;
+	}
+	writer.flush().map_err(|e| format!("failed to write CSV: {}", e))
+}
@@ -0,0 +1,3 @@
+//! Shared code for the command line tools.
+
+pub mod format;
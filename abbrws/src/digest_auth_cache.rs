@@ -12,6 +12,7 @@ use crate::Error;
 ///
 /// The cache can be used to perform requests,
 /// while caching the digest authentication challenge from the server.
+#[derive(Clone)]
 pub struct DigestAuthCache {
 	username: String,
 	password: String,
@@ -46,7 +47,7 @@ impl DigestAuthCache {
 		C: Connect + Clone + Send + Sync + 'static,
 		B: HttpBody + Send + 'static,
 		<B as HttpBody>::Data : Send,
-		<B as HttpBody>::Error : Into<Box<(dyn std::error::Error + Send + Sync + 'static)>>,
+		<B as HttpBody>::Error : Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
 	{
 		// Try the request with possibly cached challenge / response.
 		let mut request = build_request()?;
@@ -77,6 +78,39 @@ impl DigestAuthCache {
 		Ok(client.request(request).await?)
 	}
 
+	/// Create copies of the cache for requests that are performed concurrently.
+	///
+	/// Each copy answers the cached challenge with its own nonce count,
+	/// and the nonce count of this cache skips past all of them,
+	/// so that the server does not mistake the concurrent requests for replays.
+	pub fn fork(&mut self, count: usize) -> Vec<Self> {
+		(0..count).map(|_| {
+			let copy = self.clone();
+			if let Some(challenge) = self.challenge.as_mut() {
+				challenge.nc += 1;
+			}
+			copy
+		}).collect()
+	}
+
+	/// Perform a request using the given client, without retrying it.
+	///
+	/// If a cached challenge is available,
+	/// an Authorization header is added to the request containing a response to the challenge.
+	///
+	/// Use this for requests with a body that can only be sent once.
+	/// If the request fails with status 401 Unauthorized, the response is returned as-is.
+	pub async fn request_once<C, B>(&mut self, client: &Client<C, B>, mut request: Request<B>) -> Result<Response<Body>, Error>
+	where
+		C: Connect + Clone + Send + Sync + 'static,
+		B: HttpBody + Send + 'static,
+		<B as HttpBody>::Data : Send,
+		<B as HttpBody>::Error : Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+	{
+		self.add_digest_auth(&mut request);
+		Ok(client.request(request).await?)
+	}
+
 	/// If a cached challenge is available, add an Authorization header to the request.
 	///
 	/// Returns true if the header was added, false otherwise.
@@ -89,7 +123,8 @@ impl DigestAuthCache {
 		let context = digest_auth::AuthContext {
 			username: self.username.as_str().into(),
 			password: self.password.as_str().into(),
-			uri: request.uri().path().into(),
+			// The digest URI must match the request target exactly, including the query.
+			uri: request.uri().path_and_query().map(|x| x.as_str()).unwrap_or("/").into(),
 			method: convert_method(request.method()),
 			body: None,
 			cnonce: None,
@@ -105,7 +140,7 @@ impl DigestAuthCache {
 	}
 }
 
-fn convert_method(method: &hyper::Method) -> digest_auth::HttpMethod {
+fn convert_method(method: &hyper::Method) -> digest_auth::HttpMethod<'static> {
 	match method {
 		&hyper::Method::GET => digest_auth::HttpMethod::GET,
 		&hyper::Method::POST => digest_auth::HttpMethod::POST,
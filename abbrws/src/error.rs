@@ -16,17 +16,68 @@ pub struct UnexpectedContentTypeError {
 	pub expected: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct MissingHeaderError {
+	pub name: &'static str,
+}
+
+#[derive(Clone, Debug)]
+pub struct MastershipHeldError {
+	pub domain: crate::MastershipDomain,
+	pub holder: crate::MastershipHolder,
+	pub error: RemoteFailureError,
+}
+
+/// The reason a fileservice operation failed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FileServiceErrorKind {
+	NotFound,
+	NotEmpty,
+	ReadOnly,
+	AlreadyExists,
+}
+
+#[derive(Clone, Debug)]
+pub struct FileServiceError {
+	pub kind: FileServiceErrorKind,
+	pub path: String,
+	pub error: RemoteFailureError,
+}
+
+#[derive(Clone, Debug)]
+pub struct SignalKindMismatchError {
+	pub signal: String,
+	pub expected: crate::SignalKind,
+	pub actual: crate::SignalKind,
+}
+
+/// A panel setting that was rejected before it was sent to the controller.
+#[derive(Clone, Debug)]
+pub enum InvalidPanelSettingError {
+	ControllerState(crate::ControllerState),
+	OperationMode(crate::OperationMode),
+	SpeedRatio(usize),
+}
+
 #[derive(Debug)]
 pub enum Error {
 	RemoteFailure(RemoteFailureError),
+	MastershipHeld(MastershipHeldError),
 	MalformedContentType(MalformedContentTypeError),
 	UnexpectedContentType(UnexpectedContentTypeError),
+	MissingHeader(MissingHeaderError),
+	SignalKindMismatch(SignalKindMismatchError),
+	FileService(FileServiceError),
+	InvalidPanelSetting(InvalidPanelSettingError),
+	FormatRapidValue(crate::FormatRapidValueError),
 	InvalidUri(http::uri::InvalidUri),
 	Http(http::Error),
 	Hyper(hyper::Error),
 	Json(serde_json::Error),
 	InvalidHeader(hyper::header::ToStrError),
 	InvalidCookie(cookie::ParseError),
+	WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+	Io(std::io::Error),
 }
 
 impl std::fmt::Display for RemoteFailureError {
@@ -60,25 +111,113 @@ impl std::fmt::Display for UnexpectedContentTypeError {
 	}
 }
 
+impl std::fmt::Display for MissingHeaderError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "missing header in response: {}", self.name)
+	}
+}
+
+impl std::fmt::Display for MastershipHeldError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{} mastership is held by {}: {}", self.domain, self.holder, self.error)
+	}
+}
+
+impl std::fmt::Display for SignalKindMismatchError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "signal {} has type {}, expected {}", self.signal, self.actual, self.expected)
+	}
+}
+
+impl std::fmt::Display for InvalidPanelSettingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::ControllerState(x) => write!(f, "controller state can not be set to {}, only to motors on or motors off", x),
+			Self::OperationMode(x)   => write!(f, "operation mode can not be set to {}, only to automatic or manual", x),
+			Self::SpeedRatio(x)      => write!(f, "speed ratio must be between 0 and 100, got {}", x),
+		}
+	}
+}
+
+/// RWS error code for a file or directory that can not be modified.
+const FILE_SERVICE_ACCESS_DENIED: i32 = -1073442815;
+
+/// RWS error code for deleting a directory that still has entries.
+const FILE_SERVICE_DIRECTORY_NOT_EMPTY: i32 = -1073442810;
+
+/// RWS error code for creating, copying or renaming onto an existing path.
+const FILE_SERVICE_ALREADY_EXISTS: i32 = -1073442809;
+
+impl FileServiceErrorKind {
+	/// Determine the kind of a fileservice error from a failed remote call.
+	///
+	/// The kind is determined by the RWS error code.
+	/// Only if the code is not recognized, a 404 Not Found status is taken to mean [`Self::NotFound`].
+	pub fn from_remote(error: &RemoteFailureError) -> Option<Self> {
+		match error.code.map(|x| x as i32) {
+			Some(FILE_SERVICE_ACCESS_DENIED)       => Some(Self::ReadOnly),
+			Some(FILE_SERVICE_DIRECTORY_NOT_EMPTY) => Some(Self::NotEmpty),
+			Some(FILE_SERVICE_ALREADY_EXISTS)      => Some(Self::AlreadyExists),
+			_ if error.http_status == hyper::StatusCode::NOT_FOUND => Some(Self::NotFound),
+			_ => None,
+		}
+	}
+
+	/// A short description of the error kind.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::NotFound      => "not found",
+			Self::NotEmpty      => "directory not empty",
+			Self::ReadOnly      => "read-only",
+			Self::AlreadyExists => "already exists",
+		}
+	}
+}
+
+impl std::fmt::Display for FileServiceErrorKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl std::fmt::Display for FileServiceError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}: {}: {}", self.path, self.kind, self.error)
+	}
+}
+
 impl std::fmt::Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self {
 			Self::RemoteFailure(e)         => e.fmt(f),
+			Self::MastershipHeld(e)        => e.fmt(f),
 			Self::MalformedContentType(e)  => e.fmt(f),
 			Self::UnexpectedContentType(e) => e.fmt(f),
+			Self::MissingHeader(e)         => e.fmt(f),
+			Self::SignalKindMismatch(e)    => e.fmt(f),
+			Self::FileService(e)           => e.fmt(f),
+			Self::InvalidPanelSetting(e)   => e.fmt(f),
+			Self::FormatRapidValue(e)      => e.fmt(f),
 			Self::InvalidUri(e)            => e.fmt(f),
 			Self::Http(e)                  => e.fmt(f),
 			Self::Hyper(e)                 => e.fmt(f),
 			Self::Json(e)                  => e.fmt(f),
 			Self::InvalidHeader(e)         => e.fmt(f),
 			Self::InvalidCookie(e)         => e.fmt(f),
+			Self::WebSocket(e)             => e.fmt(f),
+			Self::Io(e)                    => e.fmt(f),
 		}
 	}
 }
 
 impl std::error::Error for RemoteFailureError {}
+impl std::error::Error for MastershipHeldError {}
 impl std::error::Error for MalformedContentTypeError {}
 impl std::error::Error for UnexpectedContentTypeError {}
+impl std::error::Error for MissingHeaderError {}
+impl std::error::Error for SignalKindMismatchError {}
+impl std::error::Error for FileServiceError {}
+impl std::error::Error for InvalidPanelSettingError {}
 impl std::error::Error for Error {}
 
 impl From<RemoteFailureError> for Error {
@@ -87,6 +226,12 @@ impl From<RemoteFailureError> for Error {
 	}
 }
 
+impl From<MastershipHeldError> for Error {
+	fn from(other: MastershipHeldError) -> Self {
+		Self::MastershipHeld(other)
+	}
+}
+
 impl From<MalformedContentTypeError> for Error {
 	fn from(other: MalformedContentTypeError) -> Self {
 		Self::MalformedContentType(other)
@@ -99,6 +244,36 @@ impl From<UnexpectedContentTypeError> for Error {
 	}
 }
 
+impl From<MissingHeaderError> for Error {
+	fn from(other: MissingHeaderError) -> Self {
+		Self::MissingHeader(other)
+	}
+}
+
+impl From<SignalKindMismatchError> for Error {
+	fn from(other: SignalKindMismatchError) -> Self {
+		Self::SignalKindMismatch(other)
+	}
+}
+
+impl From<FileServiceError> for Error {
+	fn from(other: FileServiceError) -> Self {
+		Self::FileService(other)
+	}
+}
+
+impl From<InvalidPanelSettingError> for Error {
+	fn from(other: InvalidPanelSettingError) -> Self {
+		Self::InvalidPanelSetting(other)
+	}
+}
+
+impl From<crate::FormatRapidValueError> for Error {
+	fn from(other: crate::FormatRapidValueError) -> Self {
+		Self::FormatRapidValue(other)
+	}
+}
+
 impl From<http::uri::InvalidUri> for Error {
 	fn from(other: http::uri::InvalidUri) -> Self {
 		Self::InvalidUri(other)
@@ -134,3 +309,15 @@ impl From<cookie::ParseError> for Error {
 		Self::InvalidCookie(other)
 	}
 }
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+	fn from(other: tokio_tungstenite::tungstenite::Error) -> Self {
+		Self::WebSocket(Box::new(other))
+	}
+}
+
+impl From<std::io::Error> for Error {
+	fn from(other: std::io::Error) -> Self {
+		Self::Io(other)
+	}
+}
@@ -0,0 +1,107 @@
+//! Typed handles for I/O signals.
+//!
+//! A handle is created by the [`Client`], which checks the signal type once.
+//! After that, the handle can only read and write values of the matching Rust type.
+
+use crate::Client;
+use crate::Error;
+use crate::SignalKind;
+use crate::SignalKindMismatchError;
+use crate::SignalValue;
+
+macro_rules! signal_handle {
+	($(#[$meta:meta])* $name:ident, $kind:ident, $value:ident, $type:ty) => {
+		$(#[$meta])*
+		#[derive(Clone, Debug, Eq, PartialEq)]
+		pub struct $name {
+			name: String,
+		}
+
+		impl $name {
+			pub(crate) fn new(name: String) -> Self {
+				Self { name }
+			}
+
+			/// The name of the signal.
+			pub fn name(&self) -> &str {
+				&self.name
+			}
+
+			/// Get the current value of the signal.
+			pub async fn get<C>(&self, client: &mut Client<C>) -> Result<$type, Error>
+			where
+				C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+			{
+				let signal = client.get_signal(&self.name).await?;
+				match signal.lvalue {
+					SignalValue::$value(x) if signal.kind == SignalKind::$kind => Ok(x),
+					_ => Err(SignalKindMismatchError {
+						signal: self.name.clone(),
+						expected: SignalKind::$kind,
+						actual: signal.kind,
+					}.into()),
+				}
+			}
+		}
+	};
+}
+
+signal_handle!(
+	/// Handle to a digital input signal.
+	DigitalInput, DigitalInput, Binary, bool
+);
+
+signal_handle!(
+	/// Handle to a digital output signal.
+	DigitalOutput, DigitalOutput, Binary, bool
+);
+
+signal_handle!(
+	/// Handle to an analog input signal.
+	AnalogInput, AnalogInput, Analog, f64
+);
+
+signal_handle!(
+	/// Handle to an analog output signal.
+	AnalogOutput, AnalogOutput, Analog, f64
+);
+
+signal_handle!(
+	/// Handle to a group input signal.
+	GroupInput, GroupInput, Group, u64
+);
+
+signal_handle!(
+	/// Handle to a group output signal.
+	GroupOutput, GroupOutput, Group, u64
+);
+
+impl DigitalOutput {
+	/// Set the value of the signal.
+	pub async fn set<C>(&self, client: &mut Client<C>, value: bool) -> Result<(), Error>
+	where
+		C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+	{
+		client.set_signal(&self.name, SignalValue::Binary(value)).await
+	}
+}
+
+impl AnalogOutput {
+	/// Set the value of the signal.
+	pub async fn set<C>(&self, client: &mut Client<C>, value: f64) -> Result<(), Error>
+	where
+		C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+	{
+		client.set_signal(&self.name, SignalValue::Analog(value)).await
+	}
+}
+
+impl GroupOutput {
+	/// Set the value of the signal.
+	pub async fn set<C>(&self, client: &mut Client<C>, value: u64) -> Result<(), Error>
+	where
+		C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+	{
+		client.set_signal(&self.name, SignalValue::Group(value)).await
+	}
+}
@@ -1,29 +1,118 @@
+//! Client for the ABB Robot Web Services (RWS) API.
+//!
+//! # Serialization
+//!
+//! Data types that implement [`serde::Serialize`] are serialized with their Rust field names.
+//! The serialized names are part of the stable API, like the fields themselves.
+//! Types with a different representation document it on the type.
+
 mod digest_auth_cache;
 use cookie::Cookie;
 use cookie::CookieJar;
 use digest_auth_cache::DigestAuthCache;
+use futures_util::Stream;
+use futures_util::StreamExt;
+use futures_util::TryStreamExt;
 use hyper::body::HttpBody;
 use std::convert::TryFrom;
 
+pub use hyper::body::Bytes;
 pub use mime::Mime;
 
 mod error;
 pub use error::Error;
 pub use error::RemoteFailureError;
+pub use error::FileServiceError;
+pub use error::FileServiceErrorKind;
+pub use error::InvalidPanelSettingError;
+pub use error::MastershipHeldError;
 pub use error::MalformedContentTypeError;
+pub use error::MissingHeaderError;
+pub use error::SignalKindMismatchError;
 pub use error::UnexpectedContentTypeError;
 
+mod handle;
+pub use handle::AnalogInput;
+pub use handle::AnalogOutput;
+pub use handle::DigitalInput;
+pub use handle::DigitalOutput;
+pub use handle::GroupInput;
+pub use handle::GroupOutput;
+
+mod mastership;
+pub use mastership::MastershipGuard;
+
 mod parse;
+pub use parse::elog::ElogFilter;
+pub use parse::elog::ElogMessage;
+pub use parse::elog::ElogSeverity;
+pub use parse::execution::Condition;
+pub use parse::execution::Cycle;
+pub use parse::execution::ExecMode;
+pub use parse::execution::ExecutionState;
+pub use parse::execution::RapidExecutionState;
+pub use parse::execution::Regain;
+pub use parse::execution::StartOptions;
+pub use parse::execution::StopMode;
+pub use parse::file_service::Device;
 pub use parse::file_service::DirEntry;
 pub use parse::file_service::Directory;
 pub use parse::file_service::File;
+pub use parse::io::DeviceState;
+pub use parse::io::IoDevice;
+pub use parse::io::IoNetwork;
+pub use parse::io::NetworkState;
+pub use parse::mastership::MastershipDomain;
+pub use parse::mastership::MastershipHolder;
+pub use parse::mastership::MastershipStatus;
+pub use parse::motion::Coordinate;
+pub use parse::panel::ControllerState;
+pub use parse::panel::OperationMode;
+pub use parse::signal::SetMode;
+pub use parse::signal::SetOptions;
 pub use parse::signal::Signal;
+pub use parse::signal::SignalDetails;
 pub use parse::signal::SignalKind;
+pub use parse::signal::SignalQuery;
 pub use parse::signal::SignalValue;
+pub use parse::signal::SimulationState;
+
+mod rapid;
+pub use rapid::ConfData;
+pub use rapid::ExtJoint;
+pub use rapid::FormatRapidValueError;
+pub use rapid::JointTarget;
+pub use rapid::Orient;
+pub use rapid::ParseRapidValueError;
+pub use rapid::Pos;
+pub use rapid::Pose;
+pub use rapid::RapidValue;
+pub use rapid::RobJoint;
+pub use rapid::RobTarget;
+pub use rapid::Spelling;
+
+mod simulation;
+pub use simulation::SimulationGuard;
+
+mod subscription;
+pub use subscription::SignalSubscription;
+
+mod sync;
+pub use sync::SkipReason;
+pub use sync::SyncAction;
+pub use sync::SyncDirection;
+pub use sync::SyncOptions;
+pub use sync::SyncPlan;
+pub use sync::SyncReason;
+
+mod transfer;
+pub use transfer::FileDownload;
+pub use transfer::Progress;
 
 mod url_encode;
 use url_encode::url_encode_query_value;
 
+#[derive(Clone)]
 pub struct Client<C = hyper::client::connect::HttpConnector> {
 	root_url: http::Uri,
 	auth_cache: DigestAuthCache,
@@ -33,6 +122,12 @@ pub struct Client<C = hyper::client::connect::HttpConnector> {
 
 type Request = hyper::Request<hyper::Body>;
 
+/// The maximum number of requests that [`Client::set_signals`] performs concurrently.
+const MAX_CONCURRENT_WRITES: usize = 8;
+
+/// The number of items to request per page from list resources.
+const PAGE_LIMIT: usize = 100;
+
 /// ABB RWS client
 ///
 /// The client manages a session with an ABB RobotWare controller.
@@ -76,17 +171,37 @@ where
 	}
 
 	/// Get a list of all signals on the robot, inclusing their current status.
+	///
+	/// The list is downloaded in pages, which are combined into a single list.
 	pub async fn get_signals(&mut self) -> Result<Vec<Signal>, Error> {
-		let url = format!("{}/rw/iosystem/signals?json=1", self.root_url).parse().unwrap();
-		let (content_type, body) = self.get(url).await?;
-		check_content_type(content_type, mime::APPLICATION_JSON)?;
-		Ok(parse::signal::parse_list(&body)?)
+		self.stream_signals().try_collect().await
+	}
+
+	/// Get all signals on the robot as a stream.
+	///
+	/// New pages are downloaded from the controller as the stream is consumed.
+	pub fn stream_signals(&mut self) -> impl Stream<Item = Result<Signal, Error>> + Send + Unpin + '_ {
+		self.stream_query_signals(&SignalQuery::new())
+	}
+
+	/// Get the signals matching a query.
+	pub async fn query_signals(&mut self, query: &SignalQuery) -> Result<Vec<Signal>, Error> {
+		self.stream_query_signals(query).try_collect().await
+	}
+
+	/// Get the signals matching a query as a stream.
+	///
+	/// New pages are downloaded from the controller as the stream is consumed.
+	pub fn stream_query_signals(&mut self, query: &SignalQuery) -> impl Stream<Item = Result<Signal, Error>> + Send + Unpin + '_ {
+		let url = format!("{}/rw/iosystem/signals?{}&json=1", self.root_url, query.query_string(PAGE_LIMIT)).parse().unwrap();
+		let limit = query.get_limit().unwrap_or(usize::MAX);
+		self.get_pages(url, parse::signal::parse_list_page).take(limit)
 	}
 
 	/// Get the details for a single signal.
 	///
 	/// You can use [`get_signals`] to get a list of all available signals.
-	pub async fn get_signal(&mut self, signal: impl AsRef<str>) -> Result<Signal, Error> {
+	pub async fn get_signal(&mut self, signal: impl AsRef<str>) -> Result<SignalDetails, Error> {
 		let url = format!("{}/rw/iosystem/signals/{}/?json=1", self.root_url, signal.as_ref()).parse().unwrap();
 		let (content_type, body) = self.get(url).await?;
 		check_content_type(content_type, mime::APPLICATION_JSON)?;
@@ -103,12 +218,434 @@ where
 		Ok(())
 	}
 
-	/// List the files in a directory.
-	pub async fn list_files(&mut self, directory: &str) -> Result<Vec<DirEntry>, Error> {
-		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, directory).parse().unwrap();
+	/// Get a typed handle to a digital input signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn digital_input(&mut self, signal: impl Into<String>) -> Result<DigitalInput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::DigitalInput).await?;
+		Ok(DigitalInput::new(signal))
+	}
+
+	/// Get a typed handle to a digital output signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn digital_output(&mut self, signal: impl Into<String>) -> Result<DigitalOutput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::DigitalOutput).await?;
+		Ok(DigitalOutput::new(signal))
+	}
+
+	/// Get a typed handle to an analog input signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn analog_input(&mut self, signal: impl Into<String>) -> Result<AnalogInput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::AnalogInput).await?;
+		Ok(AnalogInput::new(signal))
+	}
+
+	/// Get a typed handle to an analog output signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn analog_output(&mut self, signal: impl Into<String>) -> Result<AnalogOutput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::AnalogOutput).await?;
+		Ok(AnalogOutput::new(signal))
+	}
+
+	/// Get a typed handle to a group input signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn group_input(&mut self, signal: impl Into<String>) -> Result<GroupInput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::GroupInput).await?;
+		Ok(GroupInput::new(signal))
+	}
+
+	/// Get a typed handle to a group output signal.
+	///
+	/// The type of the signal is checked once, when the handle is created.
+	pub async fn group_output(&mut self, signal: impl Into<String>) -> Result<GroupOutput, Error> {
+		let signal = self.check_signal_kind(signal.into(), SignalKind::GroupOutput).await?;
+		Ok(GroupOutput::new(signal))
+	}
+
+	/// Check that a signal has the expected type, and give back the name.
+	async fn check_signal_kind(&mut self, signal: String, expected: SignalKind) -> Result<String, Error> {
+		let actual = self.get_signal(&signal).await?.kind;
+		if actual == expected {
+			Ok(signal)
+		} else {
+			Err(SignalKindMismatchError { signal, expected, actual }.into())
+		}
+	}
+
+	/// Set the value of a signal with extra options, like pulsing or delaying the change.
+	pub async fn set_signal_with(&mut self, signal: impl AsRef<str>, value: SignalValue, options: &SetOptions) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/iosystem/signals/{}/?action=set&json=1", self.root_url, signal.as_ref()).parse().unwrap();
+		self.post_form(url, options.form_data(value)).await?;
+		Ok(())
+	}
+
+	/// Pulse a digital signal high for the given duration.
+	///
+	/// The pulse is timed by the controller.
+	pub async fn pulse_signal(&mut self, signal: impl AsRef<str>, duration: std::time::Duration) -> Result<(), Error> {
+		let options = SetOptions {
+			mode: SetMode::Pulse,
+			pulses: 1,
+			active_ms: u32::try_from(duration.as_millis()).unwrap_or(u32::MAX),
+			..Default::default()
+		};
+		self.set_signal_with(signal, SignalValue::Binary(true), &options).await
+	}
+
+	/// Set the values of multiple signals.
+	///
+	/// RWS has no endpoint to write multiple signals in one request.
+	/// Instead, the first signal is written on this client, which authenticates and starts a session if needed.
+	/// The other signals are then written concurrently, reusing the session and the authentication challenge.
+	///
+	/// The result for each signal is returned in the same order as the input,
+	/// so a failure for one signal does not prevent the others from being written.
+	pub async fn set_signals<S: AsRef<str>>(&mut self, signals: &[(S, SignalValue)]) -> Vec<Result<(), Error>> {
+		let (first, rest) = match signals.split_first() {
+			Some(x) => x,
+			None => return Vec::new(),
+		};
+
+		let mut results = Vec::with_capacity(signals.len());
+		results.push(self.set_signal(&first.0, first.1).await);
+
+		let writes = self.fork(rest.len()).into_iter().zip(rest).map(|(mut client, (signal, value))| async move {
+			let result = client.set_signal(signal, *value).await;
+			(client, result)
+		});
+		let mut writes = futures_util::stream::iter(writes).buffered(MAX_CONCURRENT_WRITES);
+		while let Some((client, result)) = writes.next().await {
+			for cookie in client.cookies.iter() {
+				self.cookies.add(cookie.clone());
+			}
+			results.push(result);
+		}
+		results
+	}
+
+	/// Simulate a signal with the given value.
+	///
+	/// The signal stays simulated until the returned guard is restored or dropped,
+	/// after which the original simulation state and value of the signal are restored.
+	/// The guard uses a copy of the client, including the current session cookies.
+	///
+	/// While simulated, the value of an input can be written like that of an output.
+	pub async fn simulate_signal(&mut self, signal: impl AsRef<str>, value: SignalValue) -> Result<SimulationGuard<C>, Error> {
+		let signal = signal.as_ref();
+		let original = self.get_signal(signal).await?;
+		self.set_simulated_value(signal, value).await?;
+		let original = match original.lstate {
+			SimulationState::Simulated => Some(original.lvalue),
+			SimulationState::NotSimulated => None,
+		};
+		Ok(SimulationGuard::new(self.clone(), signal.to_string(), original))
+	}
+
+	/// Stop simulating a signal.
+	///
+	/// Normally, you should let a [`SimulationGuard`] restore the signal.
+	pub async fn unsimulate_signal(&mut self, signal: impl AsRef<str>) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/iosystem/signals/{}/?action=set&json=1", self.root_url, signal.as_ref()).parse().unwrap();
+		let data = format!("lstate={}", url_encode_query_value(SimulationState::NotSimulated.as_str()));
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Put a signal in simulated state with the given value, without creating a guard.
+	async fn set_simulated_value(&mut self, signal: &str, value: SignalValue) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/iosystem/signals/{}/?action=set&json=1", self.root_url, signal).parse().unwrap();
+		let data = format!("lstate={}&lvalue={}", SimulationState::Simulated.as_str(), value);
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Subscribe to value changes of one or more signals.
+	///
+	/// The returned [`SignalSubscription`] is a [`Stream`](futures_util::Stream)
+	/// that yields the updated signal whenever the value of a subscribed signal changes.
+	///
+	/// The subscription uses a copy of the client, including the current session cookies.
+	pub async fn subscribe_signals<S: AsRef<str>>(&mut self, signals: &[S]) -> Result<SignalSubscription<C>, Error> {
+		let mut initial = Vec::with_capacity(signals.len());
+		for signal in signals {
+			initial.push(self.get_signal(signal).await?.into());
+		}
+		SignalSubscription::new(self.clone(), initial).await
+	}
+
+	/// Get all I/O networks on the controller.
+	pub async fn get_io_networks(&mut self) -> Result<Vec<IoNetwork>, Error> {
+		let url = format!("{}/rw/iosystem/networks?start=0&limit={}&json=1", self.root_url, PAGE_LIMIT).parse().unwrap();
+		self.get_pages(url, parse::io::parse_network_page).try_collect().await
+	}
+
+	/// Get all I/O devices on the controller.
+	pub async fn get_io_devices(&mut self) -> Result<Vec<IoDevice>, Error> {
+		let url = format!("{}/rw/iosystem/devices?start=0&limit={}&json=1", self.root_url, PAGE_LIMIT).parse().unwrap();
+		self.get_pages(url, parse::io::parse_device_page).try_collect().await
+	}
+
+	/// Get a single I/O device.
+	///
+	/// The device is identified by its title, like `Local/PANEL`.
+	/// Note that the [`SignalDetails::unit`] of a signal is only the device name, like `PANEL`, without the network.
+	pub async fn get_io_device(&mut self, device: impl AsRef<str>) -> Result<IoDevice, Error> {
+		let url = format!("{}/rw/iosystem/devices/{}?json=1", self.root_url, device.as_ref()).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::io::parse_device(&body)?)
+	}
+
+	/// Enable or disable an I/O device.
+	pub async fn set_io_device_state(&mut self, device: impl AsRef<str>, state: DeviceState) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/iosystem/devices/{}?action=set&json=1", self.root_url, device.as_ref()).parse().unwrap();
+		let data = format!("lstate={}", state.as_str());
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Get the value of a RAPID symbol.
+	///
+	/// The data type of the symbol is retrieved from the controller first,
+	/// so that the value can be parsed into the matching [`RapidValue`] variant.
+	pub async fn get_rapid_symbol(&mut self, task: &str, module: &str, name: &str) -> Result<RapidValue, Error> {
+		let symbol = format!("RAPID/{}/{}/{}", task, module, name);
+
+		let url = format!("{}/rw/rapid/symbol/properties/{}?json=1", self.root_url, symbol).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		let properties = parse::rapid::parse_symbol_properties(&body)?;
+
+		let url = format!("{}/rw/rapid/symbol/data/{}?json=1", self.root_url, symbol).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::rapid::parse_symbol_data(&body, &properties)?)
+	}
+
+	/// Set the value of a RAPID symbol.
+	///
+	/// Note that this requires mastership of the RAPID domain.
+	///
+	/// Values containing infinite or NaN numbers are rejected without contacting the controller.
+	pub async fn set_rapid_symbol(&mut self, task: &str, module: &str, name: &str, value: &RapidValue) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/rapid/symbol/data/RAPID/{}/{}/{}?action=set&json=1", self.root_url, task, module, name).parse().unwrap();
+		let data = format!("value={}", url_encode_query_value(&value.to_literal()?));
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Get the execution state of the RAPID program.
+	pub async fn get_execution_state(&mut self) -> Result<ExecutionState, Error> {
+		let url = format!("{}/rw/rapid/execution?json=1", self.root_url).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::execution::parse_execution_state(&body)?)
+	}
+
+	/// Start execution of the RAPID program.
+	///
+	/// Note that this requires mastership of the RAPID domain,
+	/// and that the motors must be on.
+	pub async fn start_rapid(&mut self, options: &StartOptions) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/rapid/execution?action=start&json=1", self.root_url).parse().unwrap();
+		self.post_form(url, options.form_data()).await?;
+		Ok(())
+	}
+
+	/// Stop execution of the RAPID program.
+	pub async fn stop_rapid(&mut self, mode: StopMode) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/rapid/execution?action=stop&json=1", self.root_url).parse().unwrap();
+		let data = format!("stopmode={}&usetsp=normal", mode.as_str());
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Reset the program pointer of all RAPID tasks to the main routine.
+	///
+	/// Note that this requires mastership of the RAPID domain.
+	pub async fn reset_program_pointer(&mut self) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/rapid/execution?action=resetpp&json=1", self.root_url).parse().unwrap();
+		self.post_form(url, "").await?;
+		Ok(())
+	}
+
+	/// Get the controller state, like motors on/off or emergency stop.
+	pub async fn get_controller_state(&mut self) -> Result<ControllerState, Error> {
+		let url = format!("{}/rw/panel/ctrlstate?json=1", self.root_url).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::panel::parse_controller_state(&body)?)
+	}
+
+	/// Set the controller state.
+	///
+	/// Only [`ControllerState::MotorsOn`] and [`ControllerState::MotorsOff`] can be set.
+	/// Turning the motors on is only possible in automatic mode.
+	///
+	/// Other states are rejected with [`Error::InvalidPanelSetting`] without contacting the controller.
+	pub async fn set_controller_state(&mut self, state: ControllerState) -> Result<(), Error> {
+		if !state.is_settable() {
+			return Err(InvalidPanelSettingError::ControllerState(state).into());
+		}
+		let url : http::Uri = format!("{}/rw/panel/ctrlstate?action=setctrlstate&json=1", self.root_url).parse().unwrap();
+		let data = format!("ctrl-state={}", state.as_str());
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Get the operation mode of the controller.
+	pub async fn get_operation_mode(&mut self) -> Result<OperationMode, Error> {
+		let url = format!("{}/rw/panel/opmode?json=1", self.root_url).parse().unwrap();
 		let (content_type, body) = self.get(url).await?;
 		check_content_type(content_type, mime::APPLICATION_JSON)?;
-		Ok(parse::file_service::parse_directory_listing(&body)?)
+		Ok(parse::panel::parse_operation_mode(&body)?)
+	}
+
+	/// Set the operation mode of the controller.
+	///
+	/// Only [`OperationMode::Auto`], [`OperationMode::ManualReduced`] and [`OperationMode::ManualFull`] can be set.
+	/// Real controllers only allow this with a key switch, so this normally only works on virtual controllers.
+	///
+	/// Other modes are rejected with [`Error::InvalidPanelSetting`] without contacting the controller.
+	pub async fn set_operation_mode(&mut self, mode: OperationMode) -> Result<(), Error> {
+		if !mode.is_settable() {
+			return Err(InvalidPanelSettingError::OperationMode(mode).into());
+		}
+		let url : http::Uri = format!("{}/rw/panel/opmode?action=setopmode&json=1", self.root_url).parse().unwrap();
+		let data = format!("opmode={}", mode.as_str());
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Get the speed ratio of the controller, as percentage.
+	pub async fn get_speed_ratio(&mut self) -> Result<usize, Error> {
+		let url = format!("{}/rw/panel/speedratio?json=1", self.root_url).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::panel::parse_speed_ratio(&body)?)
+	}
+
+	/// Set the speed ratio of the controller, as percentage from 0 to 100.
+	///
+	/// Ratios above 100 are rejected with [`Error::InvalidPanelSetting`] without contacting the controller.
+	pub async fn set_speed_ratio(&mut self, ratio: usize) -> Result<(), Error> {
+		if ratio > 100 {
+			return Err(InvalidPanelSettingError::SpeedRatio(ratio).into());
+		}
+		let url : http::Uri = format!("{}/rw/panel/speedratio?action=setspeedratio&json=1", self.root_url).parse().unwrap();
+		let data = format!("speed-ratio={}", ratio);
+		self.post_form(url, data).await?;
+		Ok(())
+	}
+
+	/// Get the current joint positions of a mechanical unit, like `ROB_1`.
+	pub async fn get_joint_target(&mut self, mechunit: &str) -> Result<JointTarget, Error> {
+		let url = format!("{}/rw/motionsystem/mechunits/{}/jointtarget?json=1", self.root_url, mechunit).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::motion::parse_joint_target(&body)?)
+	}
+
+	/// Get the current Cartesian position of a mechanical unit.
+	///
+	/// The position is that of the given tool, expressed in the given coordinate system.
+	/// The work object is only used for [`Coordinate::Wobj`].
+	pub async fn get_robtarget(&mut self, mechunit: &str, tool: &str, wobj: &str, coordinate: Coordinate) -> Result<RobTarget, Error> {
+		let url = format!(
+			"{}/rw/motionsystem/mechunits/{}/robtarget?tool={}&wobj={}&coordinate={}&json=1",
+			self.root_url,
+			mechunit,
+			url_encode_query_value(tool),
+			url_encode_query_value(wobj),
+			coordinate.as_str(),
+		).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::motion::parse_robtarget(&body)?)
+	}
+
+	/// Read all messages from an event log domain.
+	///
+	/// Domain 0 is the common domain, which contains the messages of all other domains.
+	pub async fn get_event_log(&mut self, domain: usize) -> Result<Vec<ElogMessage>, Error> {
+		self.get_event_log_filtered(domain, &ElogFilter::default()).await
+	}
+
+	/// Read the messages from an event log domain that pass a filter.
+	///
+	/// If the filter has a `since` sequence number,
+	/// no more pages are downloaded once older messages are encountered.
+	/// This allows you to efficiently poll for new messages.
+	pub async fn get_event_log_filtered(&mut self, domain: usize, filter: &ElogFilter) -> Result<Vec<ElogMessage>, Error> {
+		let url = format!("{}/rw/elog/{}?lang=en&json=1", self.root_url, domain).parse().unwrap();
+		let mut pages = self.stream_pages(url, parse::elog::parse_elog_page);
+		let mut messages = Vec::new();
+		while let Some(page) = pages.try_next().await? {
+			let exhausted = filter.is_exhausted_by(&page);
+			messages.extend(page.into_iter().filter(|x| filter.matches(x)));
+			if exhausted {
+				break;
+			}
+		}
+		Ok(messages)
+	}
+
+	/// Get the mastership status of a domain.
+	pub async fn get_mastership(&mut self, domain: MastershipDomain) -> Result<MastershipStatus, Error> {
+		let url = format!("{}/rw/mastership/{}?json=1", self.root_url, domain.as_str()).parse().unwrap();
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		Ok(parse::mastership::parse_mastership_status(&body)?)
+	}
+
+	/// Request mastership of a domain.
+	///
+	/// Mastership is held until the returned guard is released or dropped.
+	/// The guard uses a copy of the client, including the current session cookies.
+	///
+	/// If another client holds mastership, a [`MastershipHeldError`] is returned,
+	/// which tells you what kind of client holds mastership.
+	pub async fn request_mastership(&mut self, domain: MastershipDomain) -> Result<MastershipGuard<C>, Error> {
+		let url : http::Uri = format!("{}/rw/mastership/{}?action=request&json=1", self.root_url, domain.as_str()).parse().unwrap();
+		match self.post_form(url, "").await {
+			Ok(_) => Ok(MastershipGuard::new(self.clone(), domain)),
+			Err(Error::RemoteFailure(error)) => {
+				// Find out if the request failed because someone else holds mastership.
+				match self.get_mastership(domain).await {
+					Ok(MastershipStatus { holder, held_by_me: false }) if holder != MastershipHolder::Nobody => {
+						Err(MastershipHeldError { domain, holder, error }.into())
+					},
+					_ => Err(error.into()),
+				}
+			},
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Release mastership of a domain.
+	///
+	/// Normally, you should let a [`MastershipGuard`] release mastership.
+	pub async fn release_mastership(&mut self, domain: MastershipDomain) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/rw/mastership/{}?action=release&json=1", self.root_url, domain.as_str()).parse().unwrap();
+		self.post_form(url, "").await?;
+		Ok(())
+	}
+
+	/// List the files in a directory.
+	pub async fn list_files(&mut self, directory: &str) -> Result<Vec<DirEntry>, Error> {
+		self.stream_files(directory).try_collect().await
+	}
+
+	/// List the contents of a directory as a stream.
+	///
+	/// New pages are downloaded from the controller as the stream is consumed.
+	pub fn stream_files(&mut self, directory: &str) -> impl Stream<Item = Result<DirEntry, Error>> + Send + Unpin + '_ {
+		let url = format!("{}/fileservice/{}/?start=0&limit={}&json=1", self.root_url, directory, PAGE_LIMIT).parse().unwrap();
+		self.get_pages(url, parse::file_service::parse_directory_page)
 	}
 
 	/// Create a directory.
@@ -116,31 +653,226 @@ where
 		if let Some((parent, child)) = directory.rsplit_once('/') {
 			let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, parent).parse().unwrap();
 			let data = format!("fs-newname={}&fs-action=create", url_encode_query_value(child));
-			self.post_form(url, data).await?;
+			self.post_form(url, data).await.map_err(|e| file_service_error(directory, e))?;
 		} else {
 			let url : http::Uri = format!("{}/fileservice/?json=1", self.root_url).parse().unwrap();
 			let data = format!("fs-newname={}&fs-action=create", url_encode_query_value(directory));
-			self.post_form(url, data).await?;
+			self.post_form(url, data).await.map_err(|e| file_service_error(directory, e))?;
 		}
 		Ok(())
 	}
 
+	/// Delete a file.
+	pub async fn delete_file(&mut self, path: &str) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
+		self.delete(url).await.map_err(|e| file_service_error(path, e))?;
+		Ok(())
+	}
+
+	/// Delete a directory.
+	///
+	/// If `recursive` is false, the directory must be empty.
+	/// Otherwise, the contents of the directory are deleted first.
+	pub async fn delete_directory(&mut self, directory: &str, recursive: bool) -> Result<(), Error> {
+		// Directories are pushed twice: once to delete the contents and once more to delete the directory itself.
+		let mut pending = vec![(directory.to_string(), !recursive)];
+		while let Some((directory, emptied)) = pending.pop() {
+			if emptied {
+				let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, directory).parse().unwrap();
+				self.delete(url).await.map_err(|e| file_service_error(&directory, e))?;
+				continue;
+			}
+
+			let entries = self.list_files(&directory).await.map_err(|e| file_service_error(&directory, e))?;
+			pending.push((directory.clone(), true));
+			for entry in entries {
+				match entry {
+					DirEntry::Directory(x) => pending.push((format!("{}/{}", directory, x.name), false)),
+					DirEntry::File(x) => self.delete_file(&format!("{}/{}", directory, x.name)).await?,
+					DirEntry::Device(_) => (),
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Rename or move a file or directory.
+	///
+	/// The new name can be a plain name to rename the entry in place,
+	/// or a full path like `$HOME/backup/config.cfg` to move it.
+	pub async fn rename(&mut self, path: &str, new_name: &str) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
+		let data = format!("fs-newname={}&fs-action=rename", url_encode_query_value(new_name));
+		self.post_form(url, data).await.map_err(|e| file_service_error(path, e))?;
+		Ok(())
+	}
+
+	/// Copy a file or directory.
+	///
+	/// If `overwrite` is false and the destination exists, the copy fails.
+	pub async fn copy(&mut self, path: &str, destination: &str, overwrite: bool) -> Result<(), Error> {
+		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
+		let data = format!("fs-newname={}&fs-action=copy&fs-overwrite={}", url_encode_query_value(destination), overwrite);
+		self.post_form(url, data).await.map_err(|e| file_service_error(path, e))?;
+		Ok(())
+	}
+
 	/// Download a file from the controller.
 	pub async fn download_file(&mut self, path: &str) -> Result<(Mime, Vec<u8>), Error> {
 		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
-		let body = self.get(url).await?;
+		let body = self.get(url).await.map_err(|e| file_service_error(path, e))?;
 		// TODO: Check that it wasn't a directory somehow.
 		// Hopefully we can use the content type. Needs experimenting.
 		Ok(body)
 	}
 
+	/// Download a file from the controller as a stream of chunks.
+	///
+	/// Unlike [`Self::download_file`], this does not buffer the whole file in memory.
+	pub async fn download_file_stream(&mut self, path: &str) -> Result<FileDownload, Error> {
+		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
+		let response = self.request_raw(|| hyper::Request::get(url.clone()).body(hyper::Body::empty())).await
+			.map_err(|e| file_service_error(path, e))?;
+		FileDownload::new(response)
+	}
+
+	/// Download a file from the controller and write it to `writer` as it comes in.
+	///
+	/// The `progress` callback is called after each chunk is written.
+	/// Returns the content type of the file.
+	pub async fn download_file_to<W>(&mut self, path: &str, mut writer: W, mut progress: impl FnMut(Progress)) -> Result<Mime, Error>
+	where
+		W: tokio::io::AsyncWrite + Unpin,
+	{
+		use tokio::io::AsyncWriteExt;
+
+		let mut download = self.download_file_stream(path).await?;
+		let mut progress_state = Progress { transferred: 0, total: download.content_length() };
+		while let Some(chunk) = download.try_next().await? {
+			writer.write_all(&chunk).await?;
+			progress_state.transferred += chunk.len() as u64;
+			progress(progress_state);
+		}
+		writer.flush().await?;
+		Ok(download.content_type().clone())
+	}
+
+	/// Download a file from the controller to a local file.
+	///
+	/// The file is downloaded to a temporary file next to `local_path`, which is moved into place when complete.
+	/// If the download fails, an existing file at `local_path` is left untouched.
+	///
+	/// See [`Self::download_file_to`] for details.
+	pub async fn download_file_to_path(&mut self, path: &str, local_path: impl AsRef<std::path::Path>, progress: impl FnMut(Progress)) -> Result<Mime, Error> {
+		let local_path = local_path.as_ref();
+		let name = local_path.file_name().unwrap_or_default().to_string_lossy();
+		let temporary = local_path.with_file_name(format!(".{}.abbrws-download", name));
+
+		let file = tokio::fs::File::create(&temporary).await?;
+		let result = match self.download_file_to(path, file, progress).await {
+			Ok(content_type) => tokio::fs::rename(&temporary, local_path).await.map(|()| content_type).map_err(Error::from),
+			Err(e) => Err(e),
+		};
+		if result.is_err() {
+			let _ = tokio::fs::remove_file(&temporary).await;
+		}
+		result
+	}
+
 	/// Upload a file to the controller.
 	pub async fn upload_file(&mut self, path: &str, content_type: Mime, data: impl Into<Vec<u8>>) -> Result<(), Error> {
 		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
-		self.put(url, content_type, data).await?;
+		self.put(url, content_type, data).await.map_err(|e| file_service_error(path, e))?;
 		Ok(())
 	}
 
+	/// Upload a file to the controller from an [`AsyncRead`](tokio::io::AsyncRead).
+	///
+	/// The data is streamed to the controller without buffering the whole file in memory.
+	/// If `length` is given, it is sent as the `Content-Length` of the upload.
+	/// The `progress` callback is called after each chunk is sent.
+	pub async fn upload_file_from<R>(&mut self, path: &str, content_type: Mime, reader: R, length: Option<u64>, progress: impl FnMut(Progress)) -> Result<(), Error>
+	where
+		R: tokio::io::AsyncRead + Unpin,
+	{
+		let url : http::Uri = format!("{}/fileservice/{}/?json=1", self.root_url, path).parse().unwrap();
+
+		// A streamed body can only be sent once, so it can not be retried after a 401 Unauthorized.
+		// Refresh the authentication challenge first with a request without body.
+		let handshake_url = format!("{}/fileservice/?json=1", self.root_url).parse().unwrap();
+		self.get(handshake_url).await?;
+
+		let (sender, body) = hyper::Body::channel();
+		let mut request = hyper::Request::put(url)
+			.header(hyper::header::CONTENT_TYPE, content_type.as_ref());
+		if let Some(length) = length {
+			request = request.header(hyper::header::CONTENT_LENGTH, length);
+		}
+		let request = request.body(body)?;
+
+		let (response, sent) = futures_util::future::join(
+			self.request_once(request),
+			transfer::send_body(reader, sender, length, progress),
+		).await;
+
+		// If the request failed, the body was probably cut short, so report the request error first.
+		response.map_err(|e| file_service_error(path, e))?;
+		sent?;
+		Ok(())
+	}
+
+	/// Upload a local file to the controller.
+	///
+	/// See [`Self::upload_file_from`] for details.
+	pub async fn upload_file_from_path(&mut self, local_path: impl AsRef<std::path::Path>, path: &str, content_type: Mime, progress: impl FnMut(Progress)) -> Result<(), Error> {
+		let file = tokio::fs::File::open(local_path).await?;
+		let length = file.metadata().await?.len();
+		self.upload_file_from(path, content_type, file, Some(length), progress).await
+	}
+
+	/// Get all items of a list resource as a stream, following the links to the next pages.
+	///
+	/// See [`Self::stream_pages`] for details.
+	fn get_pages<'a, T>(&'a mut self, url: http::Uri, parse_page: fn(&[u8]) -> serde_json::Result<parse::Page<T>>) -> impl Stream<Item = Result<T, Error>> + Send + Unpin + 'a
+	where
+		T: Send + 'a,
+	{
+		let items = self.stream_pages(url, parse_page)
+			.map_ok(|page| futures_util::stream::iter(page.into_iter().map(Ok)));
+		Box::pin(items.try_flatten())
+	}
+
+	/// Get the pages of a list resource as a stream, following the links to the next pages.
+	///
+	/// The stream ends when a next link points to a page that was already visited,
+	/// so a misbehaving server can not make it loop forever.
+	fn stream_pages<'a, T>(&'a mut self, url: http::Uri, parse_page: fn(&[u8]) -> serde_json::Result<parse::Page<T>>) -> impl Stream<Item = Result<Vec<T>, Error>> + Send + Unpin + 'a
+	where
+		T: Send + 'a,
+	{
+		let visited = std::collections::HashSet::new();
+		let pages = futures_util::stream::try_unfold((self, Some(url), visited), move |(client, url, mut visited)| async move {
+			let url = match url {
+				Some(x) if visited.insert(page_key(&x)) => x,
+				_ => return Ok(None),
+			};
+			let (page, next) = client.get_page(url, parse_page).await?;
+			Ok::<_, Error>(Some((page.items, (client, next, visited))))
+		});
+		Box::pin(pages)
+	}
+
+	/// Get a single page of a list resource, and the URL of the next page if there is one.
+	async fn get_page<T>(&mut self, url: http::Uri, parse_page: fn(&[u8]) -> serde_json::Result<parse::Page<T>>) -> Result<(parse::Page<T>, Option<http::Uri>), Error> {
+		let (content_type, body) = self.get(url).await?;
+		check_content_type(content_type, mime::APPLICATION_JSON)?;
+		let page = parse_page(&body)?;
+		let next = page.next.as_deref()
+			.map(|next| parse::resolve_link(&self.root_url, page.base.as_deref(), next))
+			.transpose()?;
+		Ok((page, next))
+	}
+
 	/// Perform a GET request.
 	async fn get(&mut self, url: http::Uri) -> Result<(Mime, Vec<u8>), Error> {
 		self.request(|| hyper::Request::get(url.clone()).body(hyper::Body::empty())).await
@@ -155,26 +887,68 @@ where
 		).await
 	}
 
-	/// Perform a POST request with form data.
+	/// Compare a local directory tree with a directory tree on the controller.
+	///
+	/// Files are compared by size, and by modification time if [`SyncOptions::utc_offset_s`] is given.
+	/// The controller reports modification times without time zone,
+	/// so without the offset a file that changed without changing size is not detected.
+	///
+	/// Read-only files at the destination are never overwritten or deleted.
+	/// If [`SyncOptions::delete`] is true, entries that only exist at the destination are deleted.
+	///
+	/// Nothing is changed until the plan is passed to [`Self::apply_sync`].
+	pub async fn plan_sync(&mut self, local: impl AsRef<std::path::Path>, remote: &str, direction: SyncDirection, options: &SyncOptions) -> Result<SyncPlan, Error> {
+		sync::plan(self, local.as_ref(), remote, direction, options).await
+	}
+
+	/// Carry out a plan made by [`Self::plan_sync`].
+	///
+	/// The `progress` callback is called before each action.
+	pub async fn apply_sync(&mut self, plan: &SyncPlan, progress: impl FnMut(&SyncAction)) -> Result<(), Error> {
+		sync::apply(self, plan, progress).await
+	}
+
+	/// Perform a DELETE request.
+	async fn delete(&mut self, url: http::Uri) -> Result<(Mime, Vec<u8>), Error> {
+		self.request(|| hyper::Request::delete(url.clone()).body(hyper::Body::empty())).await
+	}
+
+	/// Perform a PUT request.
 	async fn put(&mut self, url: http::Uri, content_type: Mime, data: impl Into<Vec<u8>>) -> Result<(Mime, Vec<u8>), Error> {
 		let data = data.into();
-		self.request(move || hyper::Request::post(url.clone())
+		self.request(move || hyper::Request::put(url.clone())
 			.header(hyper::header::CONTENT_TYPE, content_type.as_ref())
 			.body(data.clone().into())
 		).await
 	}
 
+	/// Create copies of the client for requests that are performed concurrently.
+	///
+	/// The copies start with the session cookies and the cached authentication challenge of this client.
+	fn fork(&mut self, count: usize) -> Vec<Self> {
+		self.auth_cache.fork(count).into_iter().map(|auth_cache| Self {
+			root_url: self.root_url.clone(),
+			auth_cache,
+			http_client: self.http_client.clone(),
+			cookies: self.cookies.clone(),
+		}).collect()
+	}
+
 	/// Perform a HTTP request.
 	///
 	/// This function takes care of HTTP digest authentication and cookies.
-	async fn request(&mut self, mut make_request: impl FnMut() -> http::Result<Request>) -> Result<(Mime, Vec<u8>), Error> {
-		// Copy cookies into a list of HeaderValue objects.
-		let cookie_headers : Vec<_> = self.cookies.iter().map(|cookie| {
-			// Unwrap should be fine, we already parsed it from a HeaderValue earlier.
-			let value = format!("{}={}", cookie.name(), cookie.value());
-			hyper::header::HeaderValue::from_str(&value).unwrap()
-		}).collect();
+	async fn request(&mut self, make_request: impl FnMut() -> http::Result<Request>) -> Result<(Mime, Vec<u8>), Error> {
+		let response = self.request_raw(make_request).await?;
+		let content_type = get_content_type(&response)?;
+		Ok((content_type, collect_body(response).await?))
+	}
 
+	/// Perform a HTTP request and return the raw response.
+	///
+	/// This function takes care of HTTP digest authentication and cookies.
+	/// Unsuccessful responses are converted into an error.
+	async fn request_raw(&mut self, mut make_request: impl FnMut() -> http::Result<Request>) -> Result<hyper::Response<hyper::Body>, Error> {
+		let cookie_headers = self.cookie_headers();
 		let make_request = move || {
 			let mut request = make_request()?;
 			for cookie in &cookie_headers {
@@ -185,7 +959,23 @@ where
 
 		// Perform request.
 		let response = self.auth_cache.request(&self.http_client, make_request).await?;
+		self.process_response(response).await
+	}
+
+	/// Perform a HTTP request exactly once and return the raw response.
+	///
+	/// Unlike [`Self::request_raw`], the request is not retried if the authentication challenge is outdated.
+	/// Unsuccessful responses are converted into an error.
+	async fn request_once(&mut self, mut request: Request) -> Result<hyper::Response<hyper::Body>, Error> {
+		for cookie in self.cookie_headers() {
+			request.headers_mut().append(hyper::header::COOKIE, cookie);
+		}
+		let response = self.auth_cache.request_once(&self.http_client, request).await?;
+		self.process_response(response).await
+	}
 
+	/// Parse the cookies from a response and convert unsuccessful responses into an error.
+	async fn process_response(&mut self, response: hyper::Response<hyper::Body>) -> Result<hyper::Response<hyper::Body>, Error> {
 		// Parse cookies.
 		let headers = response.headers();
 		for cookie in headers.get_all(hyper::header::SET_COOKIE) {
@@ -194,22 +984,49 @@ where
 		}
 
 		let http_status = response.status();
-		let content_type = get_content_type(&response)?;
-
 		if http_status.is_success() {
-			Ok((content_type, collect_body(response).await?))
-		} else {
-			match content_type.essence_str() {
-				"text/plain" => {
-					Err(plain_text_to_error(http_status, collect_body(response).await?).into())
-				},
-				"application/json" => {
-					let error = parse::parse_error(&collect_body(response).await?)?;
-					Err(RemoteFailureError { http_status, code: Some(error.code), message: error.message }.into())
-				},
-				_ => Err(UnexpectedContentTypeError { actual: content_type, expected: "application/json or text/plain".into() }.into()),
-			}
+			return Ok(response);
 		}
+
+		let content_type = get_content_type(&response)?;
+		match content_type.essence_str() {
+			"text/plain" => {
+				Err(plain_text_to_error(http_status, collect_body(response).await?).into())
+			},
+			"application/json" => {
+				let error = parse::parse_error(&collect_body(response).await?)?;
+				Err(RemoteFailureError { http_status, code: Some(error.code), message: error.message }.into())
+			},
+			_ => Err(UnexpectedContentTypeError { actual: content_type, expected: "application/json or text/plain".into() }.into()),
+		}
+	}
+
+	/// Copy the session cookies into a list of HeaderValue objects.
+	fn cookie_headers(&self) -> Vec<hyper::header::HeaderValue> {
+		self.cookies.iter().map(|cookie| {
+			// Unwrap should be fine, we already parsed it from a HeaderValue earlier.
+			let value = format!("{}={}", cookie.name(), cookie.value());
+			hyper::header::HeaderValue::from_str(&value).unwrap()
+		}).collect()
+	}
+}
+
+/// Get the key used to detect visited pages when following next links.
+///
+/// URLs formatted from the root URL start with a double slash, while resolved links do not.
+fn page_key(url: &http::Uri) -> String {
+	let path_and_query = url.path_and_query().map(|x| x.as_str()).unwrap_or("/");
+	format!("/{}", path_and_query.trim_start_matches('/'))
+}
+
+/// Convert remote failures of fileservice operations into a [`FileServiceError`] if the reason is known.
+fn file_service_error(path: &str, error: Error) -> Error {
+	match error {
+		Error::RemoteFailure(error) => match FileServiceErrorKind::from_remote(&error) {
+			Some(kind) => FileServiceError { kind, path: path.to_string(), error }.into(),
+			None => error.into(),
+		},
+		error => error,
 	}
 }
 
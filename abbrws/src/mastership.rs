@@ -0,0 +1,66 @@
+use crate::Client;
+use crate::Error;
+use crate::MastershipDomain;
+
+/// Guard that holds mastership of a domain.
+///
+/// Mastership is released when the guard is dropped,
+/// or when [`MastershipGuard::release`] is called.
+///
+/// Releasing mastership on drop is done by a background task on the current Tokio runtime,
+/// so any error is silently ignored.
+/// If the guard is dropped outside of a Tokio runtime, mastership is not released
+/// until the session expires.
+/// Use [`MastershipGuard::release`] to release mastership explicitly and to see any errors.
+pub struct MastershipGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	client: Option<Client<C>>,
+	domain: MastershipDomain,
+}
+
+impl<C> MastershipGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	pub(crate) fn new(client: Client<C>, domain: MastershipDomain) -> Self {
+		Self {
+			client: Some(client),
+			domain,
+		}
+	}
+
+	/// The domain that this guard holds mastership for.
+	pub fn domain(&self) -> MastershipDomain {
+		self.domain
+	}
+
+	/// Release mastership.
+	pub async fn release(mut self) -> Result<(), Error> {
+		match self.client.take() {
+			Some(mut client) => client.release_mastership(self.domain).await,
+			None => Ok(()),
+		}
+	}
+}
+
+impl<C> Drop for MastershipGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	fn drop(&mut self) {
+		let mut client = match self.client.take() {
+			Some(x) => x,
+			None => return,
+		};
+
+		if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+			let domain = self.domain;
+			runtime.spawn(async move {
+				// Nobody to report the error to, so ignore it.
+				let _ = client.release_mastership(domain).await;
+			});
+		}
+	}
+}
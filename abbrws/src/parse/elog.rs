@@ -0,0 +1,194 @@
+use serde::Deserialize;
+use serde::Deserializer;
+
+use crate::parse::hacks::deserialize_through_str;
+
+/// The severity of an event log message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ElogSeverity {
+	Info,
+	Warning,
+	Error,
+}
+
+/// A message from the event log.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ElogMessage {
+	/// The sequence number of the message in the event log domain.
+	pub sequence_number: usize,
+
+	/// The event code.
+	pub code: usize,
+
+	pub severity: ElogSeverity,
+
+	/// The time of the event, in the local time of the controller.
+	pub timestamp: String,
+
+	pub title: String,
+
+	pub description: String,
+
+	pub consequences: String,
+
+	pub causes: String,
+
+	pub actions: String,
+}
+
+/// Filter for reading the event log.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ElogFilter {
+	/// Only include messages with at least this severity.
+	pub min_severity: Option<ElogSeverity>,
+
+	/// Only include messages with a sequence number higher than this.
+	pub since: Option<usize>,
+}
+
+impl ElogFilter {
+	/// Check if a message passes the filter.
+	pub fn matches(&self, message: &ElogMessage) -> bool {
+		let severity_ok = self.min_severity.map(|x| message.severity >= x).unwrap_or(true);
+		let since_ok = self.since.map(|x| message.sequence_number > x).unwrap_or(true);
+		severity_ok && since_ok
+	}
+
+	/// Check if further pages can not contain any messages that pass the filter.
+	///
+	/// This is the case if the messages are sorted from new to old,
+	/// and the oldest message on the page is already filtered by `since`.
+	pub(crate) fn is_exhausted_by(&self, page: &[ElogMessage]) -> bool {
+		let since = match self.since {
+			Some(x) => x,
+			None => return false,
+		};
+		match (page.first(), page.last()) {
+			(Some(first), Some(last)) => first.sequence_number >= last.sequence_number && last.sequence_number <= since,
+			_ => false,
+		}
+	}
+}
+
+impl std::fmt::Display for ElogSeverity {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Info    => f.pad("info"),
+			Self::Warning => f.pad("warning"),
+			Self::Error   => f.pad("error"),
+		}
+	}
+}
+
+fn deserialize_severity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ElogSeverity, D::Error> {
+	use serde::de::Error;
+	use serde::de::Unexpected;
+
+	let value : usize = deserialize_through_str(deserializer)?;
+	match value {
+		1 => Ok(ElogSeverity::Info),
+		2 => Ok(ElogSeverity::Warning),
+		3 => Ok(ElogSeverity::Error),
+		x => Err(D::Error::invalid_value(Unexpected::Unsigned(x as u64), &"1, 2 or 3")),
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawElogMessage {
+	#[serde(rename = "_title")]
+	title_path: String,
+
+	#[serde(rename = "msgtype")]
+	#[serde(deserialize_with = "deserialize_severity")]
+	severity: ElogSeverity,
+
+	#[serde(deserialize_with = "deserialize_through_str")]
+	code: usize,
+
+	#[serde(default)]
+	tstamp: String,
+
+	#[serde(default)]
+	title: String,
+
+	#[serde(default)]
+	desc: String,
+
+	#[serde(default)]
+	conseqs: String,
+
+	#[serde(default)]
+	causes: String,
+
+	#[serde(default)]
+	actions: String,
+}
+
+impl ElogMessage {
+	fn from_raw(raw: RawElogMessage) -> serde_json::Result<Self> {
+		use serde::de::Error;
+		use serde::de::Unexpected;
+
+		// The sequence number is the last component of the title: /rw/elog/{domain}/{sequence_number}
+		let sequence_number = raw.title_path.rsplit('/').next()
+			.and_then(|x| x.parse().ok())
+			.ok_or_else(|| Error::invalid_value(Unexpected::Str(&raw.title_path), &"event log message path"))?;
+
+		Ok(Self {
+			sequence_number,
+			code: raw.code,
+			severity: raw.severity,
+			timestamp: raw.tstamp,
+			title: raw.title,
+			description: raw.desc,
+			consequences: raw.conseqs,
+			causes: raw.causes,
+			actions: raw.actions,
+		})
+	}
+}
+
+pub fn parse_elog_page(data: &[u8]) -> serde_json::Result<super::Page<ElogMessage>> {
+	super::parse_page::<RawElogMessage>(data)?
+		.try_map(ElogMessage::from_raw)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+	use assert2::check;
+
+	#[test]
+	fn test_parse_elog_page() {
+		let page = parse_elog_page(include_bytes!("../../../samples/elog.json"));
+		assert!(let Ok(_) = &page);
+		let page = page.unwrap();
+
+		check!(page.base.as_deref() == Some("http://192.168.0.5:80/rw/elog/0/"));
+		check!(page.next.as_deref() == Some("?lang=en&start=3&limit=3&json=1"));
+		assert!(page.items.len() == 3);
+		check!(page.items[0].sequence_number == 10042);
+		check!(page.items[0].code == 50204);
+		check!(page.items[0].severity == ElogSeverity::Error);
+		check!(page.items[0].title == "Motion supervision");
+		check!(page.items[1].severity == ElogSeverity::Warning);
+		check!(page.items[2].severity == ElogSeverity::Info);
+	}
+
+	#[test]
+	fn test_filter() {
+		let page = parse_elog_page(include_bytes!("../../../samples/elog.json")).unwrap();
+
+		let filter = ElogFilter { min_severity: Some(ElogSeverity::Warning), since: None };
+		check!(page.items.iter().filter(|x| filter.matches(x)).count() == 2);
+		check!(!filter.is_exhausted_by(&page.items));
+
+		let filter = ElogFilter { min_severity: None, since: Some(10041) };
+		check!(page.items.iter().filter(|x| filter.matches(x)).count() == 1);
+		check!(filter.is_exhausted_by(&page.items));
+
+		let filter = ElogFilter { min_severity: None, since: Some(10000) };
+		check!(!filter.is_exhausted_by(&page.items));
+	}
+}
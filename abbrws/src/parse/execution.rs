@@ -0,0 +1,226 @@
+use serde::Deserialize;
+
+/// The execution state of the RAPID program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum RapidExecutionState {
+	#[serde(rename = "running")]
+	Running,
+
+	#[serde(rename = "stopped")]
+	Stopped,
+}
+
+/// The cycle mode of the RAPID program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum Cycle {
+	/// Keep the current cycle mode (only valid when starting execution).
+	#[serde(rename = "asis")]
+	AsIs,
+
+	/// Run the program once.
+	#[serde(rename = "once")]
+	Once,
+
+	/// Run the program forever.
+	#[serde(rename = "forever")]
+	Forever,
+
+	/// The program was run once and the cycle has finished.
+	#[serde(rename = "oncedone")]
+	OnceDone,
+}
+
+/// The execution state and cycle mode of the RAPID program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ExecutionState {
+	pub state: RapidExecutionState,
+	pub cycle: Cycle,
+}
+
+/// How to regain the robot path when starting execution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Regain {
+	Continue,
+	Regain,
+	Clear,
+	EnterConsume,
+}
+
+/// The execution mode used when starting execution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExecMode {
+	Continue,
+	StepIn,
+	StepOver,
+	StepOut,
+	StepBack,
+	StepLast,
+	StepMotion,
+}
+
+/// Execution condition used when starting execution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Condition {
+	None,
+	CallChain,
+}
+
+/// How to stop execution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum StopMode {
+	/// Stop at the end of the current cycle.
+	Cycle,
+
+	/// Stop after the current instruction.
+	Instruction,
+
+	/// Normal stop.
+	Stop,
+
+	/// Quick stop.
+	QuickStop,
+}
+
+/// Options for starting RAPID execution.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct StartOptions {
+	pub regain: Regain,
+	pub execmode: ExecMode,
+	pub cycle: Cycle,
+	pub condition: Condition,
+
+	/// Stop at breakpoints.
+	pub stopatbp: bool,
+
+	/// Start all tasks, regardless of the task selection panel (TSP).
+	pub all_tasks_by_tsp: bool,
+}
+
+impl Default for StartOptions {
+	fn default() -> Self {
+		Self {
+			regain: Regain::Continue,
+			execmode: ExecMode::Continue,
+			cycle: Cycle::AsIs,
+			condition: Condition::None,
+			stopatbp: false,
+			all_tasks_by_tsp: false,
+		}
+	}
+}
+
+impl Cycle {
+	/// Get the name used by RWS for the cycle mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::AsIs     => "asis",
+			Self::Once     => "once",
+			Self::Forever  => "forever",
+			Self::OnceDone => "oncedone",
+		}
+	}
+}
+
+impl Regain {
+	/// Get the name used by RWS for the regain mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Continue     => "continue",
+			Self::Regain       => "regain",
+			Self::Clear        => "clear",
+			Self::EnterConsume => "enter_consume",
+		}
+	}
+}
+
+impl ExecMode {
+	/// Get the name used by RWS for the execution mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Continue   => "continue",
+			Self::StepIn     => "stepin",
+			Self::StepOver   => "stepover",
+			Self::StepOut    => "stepout",
+			Self::StepBack   => "stepback",
+			Self::StepLast   => "steplast",
+			Self::StepMotion => "stepmotion",
+		}
+	}
+}
+
+impl Condition {
+	/// Get the name used by RWS for the execution condition.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::None      => "none",
+			Self::CallChain => "callchain",
+		}
+	}
+}
+
+impl StopMode {
+	/// Get the name used by RWS for the stop mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Cycle       => "cycle",
+			Self::Instruction => "instr",
+			Self::Stop        => "stop",
+			Self::QuickStop   => "qstop",
+		}
+	}
+}
+
+impl StartOptions {
+	/// Encode the options as form data for the start action.
+	pub(crate) fn form_data(&self) -> String {
+		format!(
+			"regain={}&execmode={}&cycle={}&condition={}&stopatbp={}&alltaskbytsp={}",
+			self.regain.as_str(),
+			self.execmode.as_str(),
+			self.cycle.as_str(),
+			self.condition.as_str(),
+			if self.stopatbp { "enabled" } else { "disabled" },
+			self.all_tasks_by_tsp,
+		)
+	}
+}
+
+impl std::fmt::Display for RapidExecutionState {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Running => f.pad("running"),
+			Self::Stopped => f.pad("stopped"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawExecutionState {
+	ctrlexecstate: RapidExecutionState,
+	cycle: Cycle,
+}
+
+pub fn parse_execution_state(data: &[u8]) -> serde_json::Result<ExecutionState> {
+	let raw : RawExecutionState = super::parse_one(data)?;
+	Ok(ExecutionState {
+		state: raw.ctrlexecstate,
+		cycle: raw.cycle,
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_parse_execution_state() {
+		let parsed = parse_execution_state(include_bytes!("../../../samples/rapid_execution.json"));
+		assert!(let Ok(ExecutionState { state: RapidExecutionState::Stopped, cycle: Cycle::Forever }) = parsed);
+	}
+
+	#[test]
+	fn test_start_options_form_data() {
+		assert!(StartOptions::default().form_data() == "regain=continue&execmode=continue&cycle=asis&condition=none&stopatbp=disabled&alltaskbytsp=false");
+	}
+}
@@ -1,4 +1,8 @@
 use serde::Deserialize;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::time::Duration;
+use std::time::SystemTime;
 
 // fn deserialize_number_from_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
 // 	use serde::de::Error;
@@ -13,70 +17,171 @@ use serde::Deserialize;
 
 use crate::parse::hacks::deserialize_through_str;
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+/// A storage device on the controller.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
 pub struct Device {
-	#[serde(rename = "_title")]
-	name: String,
+	#[serde(rename(deserialize = "_title"))]
+	pub name: String,
 
-	#[serde(rename = "fs-device-type")]
-	device_type: String,
+	#[serde(rename(deserialize = "fs-device-type"))]
+	pub device_type: String,
 
-	#[serde(rename = "fs-free-space")]
+	#[serde(rename(deserialize = "fs-free-space"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	free_space: usize,
+	pub free_space: usize,
 
-	#[serde(rename = "fs-total-space")]
+	#[serde(rename(deserialize = "fs-total-space"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	total_space: usize,
+	pub total_space: usize,
 
-	#[serde(rename = "fs-enabled")]
+	#[serde(rename(deserialize = "fs-enabled"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	enabled: bool,
+	pub enabled: bool,
 
-	#[serde(rename = "fs-readonly")]
+	#[serde(rename(deserialize = "fs-readonly"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	read_only: bool,
+	pub read_only: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+/// A directory on the controller.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
 pub struct Directory {
-	#[serde(rename = "_title")]
-	name: String,
+	#[serde(rename(deserialize = "_title"))]
+	pub name: String,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+/// A file on the controller.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
 pub struct File {
-	#[serde(rename = "_title")]
-	name: String,
+	#[serde(rename(deserialize = "_title"))]
+	pub name: String,
 
-	#[serde(rename = "fs-cdate")]
-	cdate: String,
+	/// The creation time, as reported by the controller.
+	#[serde(rename(deserialize = "fs-cdate"))]
+	pub cdate: String,
 
-	#[serde(rename = "fs-mdate")]
-	mdate: String,
+	/// The modification time, as reported by the controller.
+	#[serde(rename(deserialize = "fs-mdate"))]
+	pub mdate: String,
 
-	#[serde(rename = "fs-size")]
+	/// The size of the file in bytes.
+	#[serde(rename(deserialize = "fs-size"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	size: usize,
+	pub size: usize,
 
-	#[serde(rename = "fs-readonly")]
+	#[serde(rename(deserialize = "fs-readonly"))]
 	#[serde(deserialize_with = "deserialize_through_str")]
-	read_only: bool,
+	pub read_only: bool,
 }
 
+/// An entry in a directory listing.
+///
+/// When serialized, the entry is a map with the fields of the inner value,
+/// and a `type` field with the value `directory`, `file` or `device`.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
 #[serde(tag = "_type")]
 pub enum DirEntry {
-	#[serde(rename = "fs-dir")]
+	#[serde(rename(deserialize = "fs-dir"))]
 	Directory(Directory),
 
-	#[serde(rename = "fs-file")]
+	#[serde(rename(deserialize = "fs-file"))]
 	File(File),
 
-	#[serde(rename = "fs-device")]
+	#[serde(rename(deserialize = "fs-device"))]
 	Device(Device),
 }
 
-pub fn parse_directory_listing(data: &[u8]) -> Result<Vec<DirEntry>, serde_json::Error> {
-	super::parse_vec::<DirEntry>(data)
+/// Borrowed version of [`DirEntry`] with the serialized representation.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SerializeDirEntry<'a> {
+	Directory(&'a Directory),
+	File(&'a File),
+	Device(&'a Device),
+}
+
+impl Serialize for DirEntry {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Self::Directory(x) => SerializeDirEntry::Directory(x),
+			Self::File(x) => SerializeDirEntry::File(x),
+			Self::Device(x) => SerializeDirEntry::Device(x),
+		}.serialize(serializer)
+	}
+}
+
+impl DirEntry {
+	/// The name of the entry.
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Directory(x) => &x.name,
+			Self::File(x) => &x.name,
+			Self::Device(x) => &x.name,
+		}
+	}
+}
+
+impl File {
+	/// The modification time as a [`SystemTime`], if `mdate` is in the expected format.
+	///
+	/// The controller does not report its time zone, so the time is interpreted as UTC.
+	pub fn modified(&self) -> Option<SystemTime> {
+		parse_date(&self.mdate)
+	}
+}
+
+/// Parse a fileservice date like `2022-06-01 T 10:00:00` as UTC.
+fn parse_date(input: &str) -> Option<SystemTime> {
+	let (date, time) = input.split_once('T')?;
+	let mut date = date.trim().splitn(3, '-').map(|x| x.parse::<i64>().ok());
+	let mut time = time.trim().splitn(3, ':').map(|x| x.parse::<u64>().ok());
+	let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+	let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+	if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+		return None;
+	}
+
+	// Days since the Unix epoch for a date in the proleptic Gregorian calendar.
+	let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+	let era = year.div_euclid(400);
+	let year_of_era = year.rem_euclid(400);
+	let day_of_year = (153 * month + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+	Some(SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86_400 + hour * 3600 + minute * 60 + second))
+}
+
+pub fn parse_directory_page(data: &[u8]) -> Result<super::Page<DirEntry>, serde_json::Error> {
+	super::parse_page::<DirEntry>(data)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::check;
+
+	#[test]
+	fn test_serialize_dir_entry() {
+		let entry = DirEntry::File(File {
+			name: "main.mod".into(),
+			cdate: "2022-06-01 T 10:00:00".into(),
+			mdate: "2022-06-02 T 11:00:00".into(),
+			size: 1234,
+			read_only: false,
+		});
+		check!(serde_json::to_string(&entry).unwrap() == r#"{"type":"file","name":"main.mod","cdate":"2022-06-01 T 10:00:00","mdate":"2022-06-02 T 11:00:00","size":1234,"read_only":false}"#);
+
+		let entry = DirEntry::Directory(Directory { name: "backup".into() });
+		check!(serde_json::to_string(&entry).unwrap() == r#"{"type":"directory","name":"backup"}"#);
+	}
+
+	#[test]
+	fn test_parse_date() {
+		check!(parse_date("1970-01-01 T 00:00:00") == Some(SystemTime::UNIX_EPOCH));
+		check!(parse_date("2022-06-01 T 10:00:00") == Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_654_077_600)));
+		check!(parse_date("2024-02-29T23:59:59") == Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_251_199)));
+		check!(parse_date("2022-13-01 T 10:00:00") == None);
+		check!(parse_date("yesterday") == None);
+	}
 }
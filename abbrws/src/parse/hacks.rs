@@ -19,10 +19,35 @@ impl DeserializeThroughStr for bool {
 	}
 }
 
+impl DeserializeThroughStr for f64 {
+	fn deserialize_through_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(VisitThroughStr::<f64>::default())
+	}
+}
+
 pub fn deserialize_through_str<'de, D: Deserializer<'de>, T: DeserializeThroughStr>(deserializer: D) -> Result<T, D::Error> {
 	T::deserialize_through_str(deserializer)
 }
 
+/// Deserialize an optional field through a string.
+///
+/// Use together with `#[serde(default)]` so that a missing field becomes `None`.
+pub fn deserialize_option_through_str<'de, D: Deserializer<'de>, T: DeserializeThroughStr>(deserializer: D) -> Result<Option<T>, D::Error> {
+	T::deserialize_through_str(deserializer).map(Some)
+}
+
+/// Deserialize an optional string where RWS uses `-` for a missing value.
+///
+/// Use together with `#[serde(default)]` so that a missing field also becomes `None`.
+pub fn deserialize_dash_as_none<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+	let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+	if value == "-" {
+		Ok(None)
+	} else {
+		Ok(Some(value))
+	}
+}
+
 /// Visitor that parses values either directly or from a string.
 #[derive(Default)]
 struct VisitThroughStr<T> {
@@ -40,14 +65,12 @@ impl<'de> serde::de::Visitor<'de> for VisitThroughStr<usize> {
 		value.parse().map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &"unsigned integer"))
 	}
 
-	serde::serde_if_integer128! {
-		fn visit_u128<E: serde::de::Error>(self, value: u128) -> Result<usize, E> {
-			usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
-		}
+	fn visit_u128<E: serde::de::Error>(self, value: u128) -> Result<usize, E> {
+		usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
+	}
 
-		fn visit_i128<E: serde::de::Error>(self, value: i128) -> Result<usize, E> {
-			usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
-		}
+	fn visit_i128<E: serde::de::Error>(self, value: i128) -> Result<usize, E> {
+		usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
 	}
 
 	fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<usize, E> {
@@ -67,7 +90,7 @@ impl<'de> serde::de::Visitor<'de> for VisitThroughStr<usize> {
 	}
 
 	fn visit_u16<E: serde::de::Error>(self, value: u16) -> Result<usize, E> {
-		usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
+		Ok(usize::from(value))
 	}
 
 	fn visit_i16<E: serde::de::Error>(self, value: i16) -> Result<usize, E> {
@@ -75,7 +98,7 @@ impl<'de> serde::de::Visitor<'de> for VisitThroughStr<usize> {
 	}
 
 	fn visit_u8<E: serde::de::Error>(self, value: u8) -> Result<usize, E> {
-		usize::try_from(value).map_err(|_| E::custom(format!("value out of range for usize: {}", value)))
+		Ok(usize::from(value))
 	}
 
 	fn visit_i8<E: serde::de::Error>(self, value: i8) -> Result<usize, E> {
@@ -90,11 +113,42 @@ impl<'de> serde::de::Visitor<'de> for VisitThroughStr<bool> {
 		write!(f, "boolean")
 	}
 
-	fn visit_borrowed_str<E: serde::de::Error>(self, value: &str) -> Result<bool, E> {
-		value.parse().map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &"unsigned integer"))
+	fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<bool, E> {
+		// Some resources use "TRUE" and "FALSE" instead of "true" and "false".
+		if value.eq_ignore_ascii_case("true") {
+			Ok(true)
+		} else if value.eq_ignore_ascii_case("false") {
+			Ok(false)
+		} else {
+			Err(E::invalid_value(serde::de::Unexpected::Str(value), &"boolean"))
+		}
 	}
 
 	fn visit_bool<E: serde::de::Error>(self, value: bool) -> Result<bool, E> {
 		Ok(value)
 	}
 }
+
+impl<'de> serde::de::Visitor<'de> for VisitThroughStr<f64> {
+	type Value = f64;
+
+	fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "number")
+	}
+
+	fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<f64, E> {
+		value.trim().parse().map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &"number"))
+	}
+
+	fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<f64, E> {
+		Ok(value)
+	}
+
+	fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<f64, E> {
+		Ok(value as f64)
+	}
+
+	fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<f64, E> {
+		Ok(value as f64)
+	}
+}
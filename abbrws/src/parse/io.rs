@@ -0,0 +1,158 @@
+use serde::Deserialize;
+
+use crate::parse::hacks::deserialize_dash_as_none;
+
+/// The logical state of an I/O network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum NetworkState {
+	#[serde(rename = "started")]
+	Started,
+
+	#[serde(rename = "stopped")]
+	Stopped,
+}
+
+/// The logical state of an I/O device.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum DeviceState {
+	#[serde(rename = "enabled")]
+	Enabled,
+
+	#[serde(rename = "disabled")]
+	Disabled,
+}
+
+/// An I/O network on the controller.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct IoNetwork {
+	#[serde(rename = "_title")]
+	pub title: String,
+
+	pub name: String,
+
+	/// The physical state of the network, like `running` or `halted`.
+	#[serde(rename = "pstate")]
+	pub physical_state: String,
+
+	#[serde(rename = "lstate")]
+	pub logical_state: NetworkState,
+}
+
+/// An I/O device on an I/O network.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct IoDevice {
+	/// The full path of the device, like `Local/PANEL`.
+	#[serde(rename = "_title")]
+	pub title: String,
+
+	pub name: String,
+
+	/// The physical state of the device, like `running` or `unconnected`.
+	#[serde(rename = "pstate")]
+	pub physical_state: String,
+
+	#[serde(rename = "lstate")]
+	pub logical_state: DeviceState,
+
+	/// The address of the device on the network, if it has one.
+	#[serde(default)]
+	#[serde(deserialize_with = "deserialize_dash_as_none")]
+	pub address: Option<String>,
+}
+
+impl NetworkState {
+	/// Get the name used by RWS for the network state.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Started => "started",
+			Self::Stopped => "stopped",
+		}
+	}
+}
+
+impl DeviceState {
+	/// Get the name used by RWS for the device state.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Enabled  => "enabled",
+			Self::Disabled => "disabled",
+		}
+	}
+}
+
+impl std::fmt::Display for NetworkState {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl std::fmt::Display for DeviceState {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl IoDevice {
+	/// Check if the device is enabled.
+	pub fn is_enabled(&self) -> bool {
+		self.logical_state == DeviceState::Enabled
+	}
+
+	/// The name of the network the device is on, taken from the title.
+	pub fn network(&self) -> Option<&str> {
+		self.title.rsplit_once('/').map(|(network, _)| network)
+	}
+}
+
+pub fn parse_network_page(data: &[u8]) -> serde_json::Result<super::Page<IoNetwork>> {
+	super::parse_page(data)
+}
+
+pub fn parse_device_page(data: &[u8]) -> serde_json::Result<super::Page<IoDevice>> {
+	super::parse_page(data)
+}
+
+pub fn parse_device(data: &[u8]) -> serde_json::Result<IoDevice> {
+	super::parse_one(data)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+	use assert2::check;
+
+	#[test]
+	fn test_parse_networks() {
+		let page = parse_network_page(include_bytes!("../../../samples/io_networks.json"));
+		assert!(let Ok(_) = &page);
+		let networks = page.unwrap().items;
+		assert!(networks.len() == 2);
+		check!(networks[0].name == "Local");
+		check!(networks[0].physical_state == "running");
+		check!(networks[0].logical_state == NetworkState::Started);
+		check!(networks[1].logical_state == NetworkState::Stopped);
+	}
+
+	#[test]
+	fn test_parse_devices() {
+		let page = parse_device_page(include_bytes!("../../../samples/io_devices.json"));
+		assert!(let Ok(_) = &page);
+		let devices = page.unwrap().items;
+		assert!(devices.len() == 2);
+		check!(devices[0].network() == Some("Local"));
+		check!(devices[0].is_enabled());
+		check!(devices[0].address == None);
+		check!(devices[1].name == "d652");
+		check!(devices[1].physical_state == "unconnected");
+		check!(devices[1].address.as_deref() == Some("192.168.125.100"));
+		check!(!devices[1].is_enabled());
+	}
+
+	#[test]
+	fn test_parse_device() {
+		let device = parse_device(include_bytes!("../../../samples/io_device.json"));
+		assert!(let Ok(IoDevice { logical_state: DeviceState::Enabled, .. }) = &device);
+		check!(device.unwrap().title == "Local/PANEL");
+	}
+}
@@ -0,0 +1,96 @@
+use serde::Deserialize;
+
+use crate::parse::hacks::deserialize_through_str;
+
+/// A mastership domain.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MastershipDomain {
+	/// Configuration database.
+	Cfg,
+
+	/// Motion control, like jogging and calibration.
+	Motion,
+
+	/// RAPID programs and data, also known as edit mastership.
+	Rapid,
+}
+
+/// The client holding mastership of a domain.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum MastershipHolder {
+	/// Nobody holds mastership.
+	#[serde(rename = "nomaster")]
+	Nobody,
+
+	/// A local client holds mastership, like the FlexPendant.
+	#[serde(rename = "local")]
+	Local,
+
+	/// A remote client holds mastership, like RobotStudio or another RWS session.
+	#[serde(rename = "remote")]
+	Remote,
+
+	/// The controller holds mastership internally.
+	#[serde(rename = "internal")]
+	Internal,
+
+	/// Mastership is held by a client type unknown to this library.
+	#[serde(other)]
+	Unknown,
+}
+
+/// The mastership status of a domain.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub struct MastershipStatus {
+	#[serde(rename = "mastership")]
+	pub holder: MastershipHolder,
+
+	#[serde(rename = "mastershipheldbyme")]
+	#[serde(deserialize_with = "deserialize_through_str")]
+	pub held_by_me: bool,
+}
+
+impl MastershipDomain {
+	/// Get the name used by RWS for the domain.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Cfg    => "cfg",
+			Self::Motion => "motion",
+			Self::Rapid  => "rapid",
+		}
+	}
+}
+
+impl std::fmt::Display for MastershipDomain {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl std::fmt::Display for MastershipHolder {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Nobody   => f.pad("nobody"),
+			Self::Local    => f.pad("a local client"),
+			Self::Remote   => f.pad("a remote client"),
+			Self::Internal => f.pad("the controller"),
+			Self::Unknown  => f.pad("an unknown client"),
+		}
+	}
+}
+
+pub fn parse_mastership_status(data: &[u8]) -> serde_json::Result<MastershipStatus> {
+	super::parse_one(data)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_parse_mastership_status() {
+		let parsed = parse_mastership_status(include_bytes!("../../../samples/mastership.json"));
+		assert!(let Ok(MastershipStatus { holder: MastershipHolder::Remote, held_by_me: false }) = parsed);
+	}
+}
@@ -1,8 +1,16 @@
 use serde::Deserialize;
 use serde::Deserializer;
 
+pub mod elog;
+pub mod execution;
 pub mod file_service;
+pub mod io;
+pub mod mastership;
+pub mod motion;
+pub mod panel;
+pub mod rapid;
 pub mod signal;
+pub mod subscription;
 pub mod hacks;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -31,6 +39,48 @@ struct InnerMessage<T> {
 	_state: T,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+struct Link {
+	href: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct Links {
+	base: Option<Link>,
+	next: Option<Link>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct OuterPagedMessage<T> {
+	#[serde(default)]
+	_links: Links,
+	_embedded: T,
+}
+
+/// One page of a list resource.
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+	/// The items on this page.
+	pub items: Vec<T>,
+
+	/// The base URL of the page, used to resolve relative links.
+	pub base: Option<String>,
+
+	/// The (possibly relative) link to the next page, if there is one.
+	pub next: Option<String>,
+}
+
+impl<T> Page<T> {
+	/// Convert the items on the page, keeping the links.
+	pub fn try_map<U, E>(self, convert: impl FnMut(T) -> Result<U, E>) -> Result<Page<U>, E> {
+		Ok(Page {
+			items: self.items.into_iter().map(convert).collect::<Result<_, _>>()?,
+			base: self.base,
+			next: self.next,
+		})
+	}
+}
+
 #[derive(Clone, Debug, Deserialize)]
 struct InnerErrorMessage {
 	status: ErrorStatus,
@@ -42,12 +92,51 @@ pub fn parse_error(data: &[u8]) -> Result<ErrorStatus, serde_json::Error>
 	Ok(outer._embedded.status)
 }
 
-pub fn parse_vec<'a, T>(data: &'a [u8]) -> Result<Vec<T>, serde_json::Error>
+pub fn parse_page<'a, T>(data: &'a [u8]) -> Result<Page<T>, serde_json::Error>
 where
 	T: Deserialize<'a>,
 {
-	let outer : OuterMessage<InnerMessage<Vec<T>>> = serde_json::from_slice(data)?;
-	Ok(outer._embedded._state)
+	let outer : OuterPagedMessage<InnerMessage<Vec<T>>> = serde_json::from_slice(data)?;
+	Ok(Page {
+		items: outer._embedded._state,
+		base: outer._links.base.map(|x| x.href),
+		next: outer._links.next.map(|x| x.href),
+	})
+}
+
+/// Resolve a link from a response relative to the base URL of the response.
+///
+/// The scheme and host are always taken from `root_url`,
+/// since the controller may not know the address we use to reach it.
+pub fn resolve_link(root_url: &http::Uri, base: Option<&str>, href: &str) -> Result<http::Uri, http::uri::InvalidUri> {
+	let base_path = base.map(strip_scheme_and_authority).unwrap_or("/");
+	let base_path = base_path.split('?').next().unwrap_or_default();
+
+	let path = if href.starts_with("http://") || href.starts_with("https://") {
+		strip_scheme_and_authority(href).to_string()
+	} else if href.starts_with('/') {
+		href.to_string()
+	} else if href.starts_with('?') {
+		format!("{}{}", base_path, href)
+	} else {
+		let directory = &base_path[..base_path.rfind('/').map(|x| x + 1).unwrap_or(0)];
+		format!("{}{}", directory, href)
+	};
+
+	let scheme = root_url.scheme_str().unwrap_or("http");
+	let authority = root_url.authority().map(|x| x.as_str()).unwrap_or_default();
+	format!("{}://{}{}", scheme, authority, path).parse()
+}
+
+/// Strip the scheme and authority from an absolute URL, leaving the path and query.
+fn strip_scheme_and_authority(url: &str) -> &str {
+	match url.find("://") {
+		Some(start) => {
+			let rest = &url[start + 3..];
+			rest.find('/').map(|x| &rest[x..]).unwrap_or("/")
+		},
+		None => url,
+	}
 }
 
 pub fn parse_one<'a, T>(data: &'a [u8]) -> Result<T, serde_json::Error>
@@ -67,4 +156,16 @@ mod test {
 	fn test_parse_bad_signal() {
 		assert!(let Ok(ErrorStatus { code: 0xc0048409, .. }) = parse_error(include_bytes!("../../../samples/bad_signal.json")));
 	}
+
+	#[test]
+	fn test_resolve_link() {
+		let root : http::Uri = "http://10.0.0.1:8080".parse().unwrap();
+		let base = Some("http://192.168.0.5:80/rw/elog/0/");
+		let resolve = |base, href| resolve_link(&root, base, href).unwrap().to_string();
+		assert!(resolve(base, "?start=10&json=1") == "http://10.0.0.1:8080/rw/elog/0/?start=10&json=1");
+		assert!(resolve(base, "1?json=1") == "http://10.0.0.1:8080/rw/elog/0/1?json=1");
+		assert!(resolve(base, "/rw/iosystem/signals?json=1") == "http://10.0.0.1:8080/rw/iosystem/signals?json=1");
+		assert!(resolve(base, "http://192.168.0.5/rw/elog/1/") == "http://10.0.0.1:8080/rw/elog/1/");
+		assert!(resolve(None, "signals?json=1") == "http://10.0.0.1:8080/signals?json=1");
+	}
 }
@@ -0,0 +1,158 @@
+use serde::Deserialize;
+
+use crate::parse::hacks::deserialize_through_str;
+use crate::rapid::ConfData;
+use crate::rapid::ExtJoint;
+use crate::rapid::JointTarget;
+use crate::rapid::Orient;
+use crate::rapid::Pos;
+use crate::rapid::RobJoint;
+use crate::rapid::RobTarget;
+
+/// The coordinate system to express a Cartesian position in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Coordinate {
+	/// The base frame of the robot.
+	Base,
+
+	/// The world frame.
+	World,
+
+	/// The frame of the active tool.
+	Tool,
+
+	/// The frame of the work object.
+	Wobj,
+}
+
+impl Coordinate {
+	/// Get the name used by RWS for the coordinate system.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Base  => "Base",
+			Self::World => "World",
+			Self::Tool  => "Tool",
+			Self::Wobj  => "Wobj",
+		}
+	}
+}
+
+impl std::fmt::Display for Coordinate {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Base  => f.pad("base"),
+			Self::World => f.pad("world"),
+			Self::Tool  => f.pad("tool"),
+			Self::Wobj  => f.pad("work object"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawExtJoint {
+	#[serde(deserialize_with = "deserialize_through_str")] eax_a: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] eax_b: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] eax_c: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] eax_d: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] eax_e: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] eax_f: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawJointTarget {
+	#[serde(deserialize_with = "deserialize_through_str")] rax_1: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] rax_2: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] rax_3: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] rax_4: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] rax_5: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] rax_6: f64,
+
+	#[serde(flatten)]
+	extax: RawExtJoint,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawRobTarget {
+	#[serde(deserialize_with = "deserialize_through_str")] x: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] y: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] z: f64,
+
+	#[serde(deserialize_with = "deserialize_through_str")] q1: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] q2: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] q3: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] q4: f64,
+
+	#[serde(deserialize_with = "deserialize_through_str")] cf1: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] cf4: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] cf6: f64,
+	#[serde(deserialize_with = "deserialize_through_str")] cfx: f64,
+
+	#[serde(flatten)]
+	extax: RawExtJoint,
+}
+
+impl From<RawExtJoint> for ExtJoint {
+	fn from(raw: RawExtJoint) -> Self {
+		Self {
+			eax_a: raw.eax_a,
+			eax_b: raw.eax_b,
+			eax_c: raw.eax_c,
+			eax_d: raw.eax_d,
+			eax_e: raw.eax_e,
+			eax_f: raw.eax_f,
+		}
+	}
+}
+
+pub fn parse_joint_target(data: &[u8]) -> serde_json::Result<JointTarget> {
+	let raw : RawJointTarget = super::parse_one(data)?;
+	Ok(JointTarget {
+		robax: RobJoint {
+			rax_1: raw.rax_1,
+			rax_2: raw.rax_2,
+			rax_3: raw.rax_3,
+			rax_4: raw.rax_4,
+			rax_5: raw.rax_5,
+			rax_6: raw.rax_6,
+		},
+		extax: raw.extax.into(),
+	})
+}
+
+pub fn parse_robtarget(data: &[u8]) -> serde_json::Result<RobTarget> {
+	let raw : RawRobTarget = super::parse_one(data)?;
+	Ok(RobTarget {
+		trans: Pos { x: raw.x, y: raw.y, z: raw.z },
+		rot: Orient { q1: raw.q1, q2: raw.q2, q3: raw.q3, q4: raw.q4 },
+		robconf: ConfData { cf1: raw.cf1, cf4: raw.cf4, cf6: raw.cf6, cfx: raw.cfx },
+		extax: raw.extax.into(),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_parse_joint_target() {
+		let parsed = parse_joint_target(include_bytes!("../../../samples/jointtarget.json"));
+		assert!(let Ok(_) = &parsed);
+		assert!(parsed.unwrap() == JointTarget {
+			robax: RobJoint { rax_1: 0.12, rax_2: -24.5, rax_3: 31.02, rax_4: 0.0, rax_5: 83.48, rax_6: -179.99 },
+			extax: ExtJoint::unused(),
+		});
+	}
+
+	#[test]
+	fn test_parse_robtarget() {
+		let parsed = parse_robtarget(include_bytes!("../../../samples/robtarget.json"));
+		assert!(let Ok(_) = &parsed);
+		assert!(parsed.unwrap() == RobTarget {
+			trans: Pos { x: 515.2, y: -0.5, z: 712.25 },
+			rot: Orient { q1: 0.5, q2: -0.5, q3: 0.5, q4: 0.5 },
+			robconf: ConfData { cf1: 0.0, cf4: -1.0, cf6: 0.0, cfx: 1.0 },
+			extax: ExtJoint::unused(),
+		});
+	}
+}
@@ -0,0 +1,182 @@
+use serde::Deserialize;
+
+use crate::parse::hacks::deserialize_through_str;
+
+/// The state of the controller, as shown on the control panel.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum ControllerState {
+	#[serde(rename = "init")]
+	Init,
+
+	#[serde(rename = "motoron")]
+	MotorsOn,
+
+	#[serde(rename = "motoroff")]
+	MotorsOff,
+
+	#[serde(rename = "guardstop")]
+	GuardStop,
+
+	#[serde(rename = "emergencystop")]
+	EmergencyStop,
+
+	#[serde(rename = "emergencystopreset")]
+	EmergencyStopReset,
+
+	#[serde(rename = "sysfail")]
+	SystemFailure,
+}
+
+/// The operating mode of the controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+pub enum OperationMode {
+	#[serde(rename = "INIT")]
+	Init,
+
+	/// Automatic mode, waiting for confirmation of the change.
+	#[serde(rename = "AUTO_CH")]
+	AutoChange,
+
+	/// Manual full speed mode, waiting for confirmation of the change.
+	#[serde(rename = "MANF_CH")]
+	ManualFullChange,
+
+	/// Manual mode with reduced speed.
+	#[serde(rename = "MANR")]
+	ManualReduced,
+
+	/// Manual mode with full speed.
+	#[serde(rename = "MANF")]
+	ManualFull,
+
+	/// Automatic mode.
+	#[serde(rename = "AUTO")]
+	Auto,
+
+	#[serde(rename = "UNDEF")]
+	Undefined,
+}
+
+impl ControllerState {
+	/// Get the name used by RWS for the controller state.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Init               => "init",
+			Self::MotorsOn           => "motoron",
+			Self::MotorsOff          => "motoroff",
+			Self::GuardStop          => "guardstop",
+			Self::EmergencyStop      => "emergencystop",
+			Self::EmergencyStopReset => "emergencystopreset",
+			Self::SystemFailure      => "sysfail",
+		}
+	}
+
+	/// Check if the controller state can be set through RWS.
+	pub fn is_settable(&self) -> bool {
+		matches!(self, Self::MotorsOn | Self::MotorsOff)
+	}
+}
+
+impl OperationMode {
+	/// Get the name used by RWS when setting the operation mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Init             => "init",
+			Self::AutoChange       => "auto_ch",
+			Self::ManualFullChange => "manf_ch",
+			Self::ManualReduced    => "man",
+			Self::ManualFull       => "manf",
+			Self::Auto             => "auto",
+			Self::Undefined        => "undef",
+		}
+	}
+
+	/// Check if the operation mode can be set through RWS.
+	pub fn is_settable(&self) -> bool {
+		matches!(self, Self::Auto | Self::ManualReduced | Self::ManualFull)
+	}
+}
+
+impl std::fmt::Display for ControllerState {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Init               => f.pad("initializing"),
+			Self::MotorsOn           => f.pad("motors on"),
+			Self::MotorsOff          => f.pad("motors off"),
+			Self::GuardStop          => f.pad("guard stop"),
+			Self::EmergencyStop      => f.pad("emergency stop"),
+			Self::EmergencyStopReset => f.pad("emergency stop reset"),
+			Self::SystemFailure      => f.pad("system failure"),
+		}
+	}
+}
+
+impl std::fmt::Display for OperationMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Init             => f.pad("initializing"),
+			Self::AutoChange       => f.pad("changing to automatic"),
+			Self::ManualFullChange => f.pad("changing to manual full speed"),
+			Self::ManualReduced    => f.pad("manual reduced speed"),
+			Self::ManualFull       => f.pad("manual full speed"),
+			Self::Auto             => f.pad("automatic"),
+			Self::Undefined        => f.pad("undefined"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawControllerState {
+	ctrlstate: ControllerState,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawOperationMode {
+	opmode: OperationMode,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawSpeedRatio {
+	#[serde(deserialize_with = "deserialize_through_str")]
+	speedratio: usize,
+}
+
+pub fn parse_controller_state(data: &[u8]) -> serde_json::Result<ControllerState> {
+	Ok(super::parse_one::<RawControllerState>(data)?.ctrlstate)
+}
+
+pub fn parse_operation_mode(data: &[u8]) -> serde_json::Result<OperationMode> {
+	Ok(super::parse_one::<RawOperationMode>(data)?.opmode)
+}
+
+pub fn parse_speed_ratio(data: &[u8]) -> serde_json::Result<usize> {
+	Ok(super::parse_one::<RawSpeedRatio>(data)?.speedratio)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn message(state: &str) -> Vec<u8> {
+		format!(r#"{{"_links":{{}},"_embedded":{{"_state":[{{"_type":"pnl","_title":"panel",{}}}]}}}}"#, state).into_bytes()
+	}
+
+	#[test]
+	fn test_parse_controller_state() {
+		assert!(let Ok(ControllerState::MotorsOn) = parse_controller_state(&message(r#""ctrlstate":"motoron""#)));
+		assert!(let Ok(ControllerState::GuardStop) = parse_controller_state(&message(r#""ctrlstate":"guardstop""#)));
+		assert!(let Err(_) = parse_controller_state(&message(r#""ctrlstate":"bogus""#)));
+	}
+
+	#[test]
+	fn test_parse_operation_mode() {
+		assert!(let Ok(OperationMode::Auto) = parse_operation_mode(&message(r#""opmode":"AUTO""#)));
+		assert!(let Ok(OperationMode::ManualReduced) = parse_operation_mode(&message(r#""opmode":"MANR""#)));
+	}
+
+	#[test]
+	fn test_parse_speed_ratio() {
+		assert!(let Ok(75) = parse_speed_ratio(&message(r#""speedratio":"75""#)));
+	}
+}
@@ -0,0 +1,179 @@
+use serde::Deserialize;
+
+use crate::parse::hacks::deserialize_through_str;
+use crate::rapid::ConfData;
+use crate::rapid::ExtJoint;
+use crate::rapid::JointTarget;
+use crate::rapid::Orient;
+use crate::rapid::Pos;
+use crate::rapid::Pose;
+use crate::rapid::RapidValue;
+use crate::rapid::RobJoint;
+use crate::rapid::RobTarget;
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawSymbolData {
+	value: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct RawSymbolProperties {
+	dattyp: String,
+
+	#[serde(deserialize_with = "deserialize_through_str")]
+	ndim: usize,
+}
+
+/// The properties of a RAPID symbol needed to interpret its value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolProperties {
+	pub data_type: String,
+	pub dimensions: usize,
+}
+
+pub fn parse_symbol_properties(data: &[u8]) -> serde_json::Result<SymbolProperties> {
+	let raw : RawSymbolProperties = super::parse_one(data)?;
+	Ok(SymbolProperties {
+		data_type: raw.dattyp,
+		dimensions: raw.ndim,
+	})
+}
+
+pub fn parse_symbol_data(data: &[u8], properties: &SymbolProperties) -> serde_json::Result<RapidValue> {
+	let raw : RawSymbolData = super::parse_one(data)?;
+	let value = raw.value.parse().map_err(serde::de::Error::custom)?;
+	interpret(value, &properties.data_type, properties.dimensions)
+}
+
+/// Interpret an untyped value as the given data type.
+fn interpret(value: RapidValue, data_type: &str, dimensions: usize) -> serde_json::Result<RapidValue> {
+	use serde::de::Error;
+
+	if dimensions > 0 {
+		return match value {
+			RapidValue::Record(elements) => {
+				let elements = elements.into_iter()
+					.map(|x| interpret(x, data_type, dimensions - 1))
+					.collect::<Result<_, _>>()?;
+				Ok(RapidValue::Array(elements))
+			},
+			_ => Err(Error::custom(format!("expected array of {}", data_type))),
+		};
+	}
+
+	match (data_type, value) {
+		("num", x @ RapidValue::Num(..)) => Ok(x),
+		("dnum", x @ RapidValue::Num(..)) => Ok(x),
+		("bool", x @ RapidValue::Bool(_)) => Ok(x),
+		("string", x @ RapidValue::String(..)) => Ok(x),
+		("robtarget", RapidValue::Record(fields)) => {
+			let [trans, rot, robconf, extax] = fields_array(fields, "robtarget")?;
+			Ok(RapidValue::RobTarget(RobTarget {
+				trans: to_pos(trans)?,
+				rot: to_orient(rot)?,
+				robconf: to_confdata(robconf)?,
+				extax: to_extjoint(extax)?,
+			}))
+		},
+		("jointtarget", RapidValue::Record(fields)) => {
+			let [robax, extax] = fields_array(fields, "jointtarget")?;
+			Ok(RapidValue::JointTarget(JointTarget {
+				robax: to_robjoint(robax)?,
+				extax: to_extjoint(extax)?,
+			}))
+		},
+		("pose", RapidValue::Record(fields)) => {
+			let [trans, rot] = fields_array(fields, "pose")?;
+			Ok(RapidValue::Pose(Pose {
+				trans: to_pos(trans)?,
+				rot: to_orient(rot)?,
+			}))
+		},
+		("num", _) | ("dnum", _) | ("bool", _) | ("string", _) | ("robtarget", _) | ("jointtarget", _) | ("pose", _) => {
+			Err(Error::custom(format!("invalid value for data type {}", data_type)))
+		},
+		(_, x) => Ok(x),
+	}
+}
+
+fn fields_array<const N: usize>(fields: Vec<RapidValue>, data_type: &str) -> serde_json::Result<[RapidValue; N]> {
+	use serde::de::Error;
+	let len = fields.len();
+	std::convert::TryInto::try_into(fields)
+		.map_err(|_| Error::custom(format!("expected {} fields for {}, got {}", N, data_type, len)))
+}
+
+fn nums<const N: usize>(value: RapidValue, data_type: &str) -> serde_json::Result<[f64; N]> {
+	use serde::de::Error;
+	let fields = match value {
+		RapidValue::Record(x) => x,
+		_ => return Err(Error::custom(format!("expected record for {}", data_type))),
+	};
+
+	let fields : [RapidValue; N] = fields_array(fields, data_type)?;
+	let mut result = [0.0; N];
+	for (output, field) in result.iter_mut().zip(fields) {
+		match field {
+			RapidValue::Num(x, _) => *output = x,
+			_ => return Err(Error::custom(format!("expected num field for {}", data_type))),
+		}
+	}
+	Ok(result)
+}
+
+fn to_pos(value: RapidValue) -> serde_json::Result<Pos> {
+	let [x, y, z] = nums(value, "pos")?;
+	Ok(Pos { x, y, z })
+}
+
+fn to_orient(value: RapidValue) -> serde_json::Result<Orient> {
+	let [q1, q2, q3, q4] = nums(value, "orient")?;
+	Ok(Orient { q1, q2, q3, q4 })
+}
+
+fn to_confdata(value: RapidValue) -> serde_json::Result<ConfData> {
+	let [cf1, cf4, cf6, cfx] = nums(value, "confdata")?;
+	Ok(ConfData { cf1, cf4, cf6, cfx })
+}
+
+fn to_robjoint(value: RapidValue) -> serde_json::Result<RobJoint> {
+	let [rax_1, rax_2, rax_3, rax_4, rax_5, rax_6] = nums(value, "robjoint")?;
+	Ok(RobJoint { rax_1, rax_2, rax_3, rax_4, rax_5, rax_6 })
+}
+
+fn to_extjoint(value: RapidValue) -> serde_json::Result<ExtJoint> {
+	let [eax_a, eax_b, eax_c, eax_d, eax_e, eax_f] = nums(value, "extjoint")?;
+	Ok(ExtJoint { eax_a, eax_b, eax_c, eax_d, eax_e, eax_f })
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+	use assert2::check;
+
+	#[test]
+	fn test_parse_robtarget() {
+		let properties = parse_symbol_properties(include_bytes!("../../../samples/rapid_properties.json")).unwrap();
+		check!(properties == SymbolProperties { data_type: "robtarget".into(), dimensions: 0 });
+
+		let parsed = parse_symbol_data(include_bytes!("../../../samples/rapid_robtarget.json"), &properties);
+		assert!(let Ok(RapidValue::RobTarget(_)) = &parsed);
+		let parsed = parsed.unwrap();
+		check!(parsed == RapidValue::RobTarget(RobTarget {
+			trans: Pos { x: 500.0, y: 0.0, z: 600.0 },
+			rot: Orient { q1: 1.0, q2: 0.0, q3: 0.0, q4: 0.0 },
+			robconf: ConfData { cf1: 0.0, cf4: 0.0, cf6: 0.0, cfx: 0.0 },
+			extax: ExtJoint::unused(),
+		}));
+		check!(parsed.to_string() == "[[500,0,600],[1,0,0,0],[0,0,0,0],[9E9,9E9,9E9,9E9,9E9,9E9]]");
+	}
+
+	#[test]
+	fn test_interpret() {
+		check!(let Ok(RapidValue::Array(_)) = interpret("[1,2,3]".parse().unwrap(), "num", 1));
+		check!(let Ok(RapidValue::Record(_)) = interpret("[1,TRUE]".parse().unwrap(), "mydata", 0));
+		check!(let Err(_) = interpret("[1,2]".parse().unwrap(), "pose", 0));
+		check!(let Err(_) = interpret("TRUE".parse().unwrap(), "num", 0));
+	}
+}
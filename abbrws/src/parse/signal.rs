@@ -1,6 +1,15 @@
 use serde::Deserialize;
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::time::Duration;
+use std::time::SystemTime;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+use crate::parse::hacks::deserialize_option_through_str;
+
+/// The type of an I/O signal.
+///
+/// When serialized, the type is written as the RWS abbreviation, like `DI` or `GO`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize, Serialize)]
 pub enum SignalKind {
 	#[serde(rename = "DI")]
 	DigitalInput,
@@ -21,13 +30,41 @@ pub enum SignalKind {
 	GroupOutput,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// Whether the logical value of a signal is simulated.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum SimulationState {
+	#[serde(rename = "not simulated")]
+	NotSimulated,
+
+	#[serde(rename = "simulated")]
+	Simulated,
+}
+
+/// The value of an I/O signal.
+///
+/// When serialized, the value is written as a plain boolean or number.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum SignalValue {
 	Binary(bool),
 	Analog(f64),
 	Group(u64),
 }
 
+impl SignalKind {
+	/// Get the name used by RWS for the signal type.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			SignalKind::DigitalInput  => "DI",
+			SignalKind::DigitalOutput => "DO",
+			SignalKind::AnalogInput   => "AI",
+			SignalKind::AnalogOutput  => "AO",
+			SignalKind::GroupInput    => "GI",
+			SignalKind::GroupOutput   => "GO",
+		}
+	}
+}
+
 impl std::fmt::Display for SignalKind {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self {
@@ -41,6 +78,68 @@ impl std::fmt::Display for SignalKind {
 	}
 }
 
+impl SimulationState {
+	/// Get the name used by RWS for the simulation state.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::NotSimulated => "not simulated",
+			Self::Simulated    => "simulated",
+		}
+	}
+}
+
+impl std::fmt::Display for SimulationState {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::NotSimulated => f.pad("not simulated"),
+			Self::Simulated    => f.pad("simulated"),
+		}
+	}
+}
+
+impl SignalValue {
+	/// Get a single bit of a binary or group value.
+	///
+	/// A binary value only has bit 0.
+	/// Returns `None` for analog values and for bits that are out of range.
+	pub fn bit(&self, n: u32) -> Option<bool> {
+		let (bits, width) = self.bits_with_width()?;
+		if n < width {
+			Some(bits >> n & 1 == 1)
+		} else {
+			None
+		}
+	}
+
+	/// Get the raw bits of a binary or group value.
+	///
+	/// Returns `None` for analog values.
+	pub fn bits(&self) -> Option<u64> {
+		self.bits_with_width().map(|(bits, _)| bits)
+	}
+
+	/// Get a copy of a group value with a single bit changed.
+	///
+	/// Returns `None` for analog and binary values, and for bits that are out of range.
+	pub fn with_bit(&self, n: u32, value: bool) -> Option<Self> {
+		match self {
+			Self::Group(x) if n < u64::BITS => {
+				let mask = 1 << n;
+				Some(Self::Group(if value { x | mask } else { x & !mask }))
+			},
+			_ => None,
+		}
+	}
+
+	fn bits_with_width(&self) -> Option<(u64, u32)> {
+		match self {
+			Self::Binary(x) => Some((u64::from(*x), 1)),
+			Self::Group(x) => Some((*x, u64::BITS)),
+			Self::Analog(_) => None,
+		}
+	}
+}
+
 impl std::fmt::Display for SignalValue {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		match self {
@@ -81,20 +180,232 @@ impl std::str::FromStr for SignalValue {
 	}
 }
 
+/// How a signal value is applied by the controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SetMode {
+	/// Set the value directly.
+	Value,
+
+	/// Invert the current value of a digital signal.
+	Invert,
+
+	/// Pulse the signal to the value, and back again.
+	Pulse,
+
+	/// Set the value after a delay.
+	Delay,
+}
+
+impl SetMode {
+	/// Get the name used by RWS for the set mode.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Value  => "value",
+			Self::Invert => "invert",
+			Self::Pulse  => "pulse",
+			Self::Delay  => "delay",
+		}
+	}
+}
+
+/// Options for setting a signal.
+///
+/// The timing is handled by the controller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SetOptions {
+	pub mode: SetMode,
+
+	/// The number of pulses, for [`SetMode::Pulse`].
+	pub pulses: u32,
+
+	/// The time in milliseconds that the signal is active during each pulse.
+	pub active_ms: u32,
+
+	/// The time in milliseconds that the signal is passive between pulses.
+	pub passive_ms: u32,
+
+	/// The delay in milliseconds before the value is set, for [`SetMode::Delay`].
+	pub delay_ms: u32,
+}
+
+impl Default for SetOptions {
+	fn default() -> Self {
+		Self {
+			mode: SetMode::Value,
+			pulses: 1,
+			active_ms: 0,
+			passive_ms: 0,
+			delay_ms: 0,
+		}
+	}
+}
+
+impl SetOptions {
+	/// Encode the options as form data for the set action.
+	pub(crate) fn form_data(&self, value: SignalValue) -> String {
+		let mut data = format!("lvalue={}&mode={}", value, self.mode.as_str());
+		match self.mode {
+			SetMode::Value | SetMode::Invert => (),
+			SetMode::Pulse => data += &format!("&Pulses={}&ActivePulse={}&PassivePulse={}", self.pulses, self.active_ms, self.passive_ms),
+			SetMode::Delay => data += &format!("&Delay={}", self.delay_ms),
+		}
+		data
+	}
+}
+
+/// A query for a subset of the I/O signals.
+///
+/// The filtering is done by the controller, so only matching signals are downloaded.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SignalQuery {
+	name: Option<String>,
+	kind: Option<SignalKind>,
+	category: Option<String>,
+	network: Option<String>,
+	device: Option<String>,
+	start: usize,
+	limit: Option<usize>,
+}
+
+impl SignalQuery {
+	/// Create a query that matches all signals.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Only match signals with the given name.
+	pub fn name(mut self, name: impl Into<String>) -> Self {
+		self.name = Some(name.into());
+		self
+	}
+
+	/// Only match signals of the given type.
+	pub fn kind(mut self, kind: SignalKind) -> Self {
+		self.kind = Some(kind);
+		self
+	}
+
+	/// Only match signals in the given category, like `safety`.
+	pub fn category(mut self, category: impl Into<String>) -> Self {
+		self.category = Some(category.into());
+		self
+	}
+
+	/// Only match signals on the given I/O network.
+	pub fn network(mut self, network: impl Into<String>) -> Self {
+		self.network = Some(network.into());
+		self
+	}
+
+	/// Only match signals on the given I/O device.
+	pub fn device(mut self, device: impl Into<String>) -> Self {
+		self.device = Some(device.into());
+		self
+	}
+
+	/// Skip the first `start` matching signals.
+	pub fn start(mut self, start: usize) -> Self {
+		self.start = start;
+		self
+	}
+
+	/// Return at most `limit` signals.
+	pub fn limit(mut self, limit: usize) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// The maximum number of signals to return, if any.
+	pub(crate) fn get_limit(&self) -> Option<usize> {
+		self.limit
+	}
+
+	/// Encode the query as query string for the signals resource.
+	///
+	/// If no limit was set, `page_limit` is used as page size.
+	pub(crate) fn query_string(&self, page_limit: usize) -> String {
+		use crate::url_encode::url_encode_query_value;
+
+		let mut query = String::new();
+		let filters = [
+			("name", self.name.as_deref()),
+			("type", self.kind.as_ref().map(SignalKind::as_str)),
+			("category", self.category.as_deref()),
+			("network", self.network.as_deref()),
+			("device", self.device.as_deref()),
+		];
+		for (key, value) in filters {
+			if let Some(value) = value {
+				query += &format!("{}={}&", key, url_encode_query_value(value));
+			}
+		}
+		query += &format!("start={}&limit={}", self.start, self.limit.unwrap_or(page_limit));
+		query
+	}
+}
+
+/// A single signal, as reported by the controller.
+///
+/// Only the fields shared with the signal list are required,
+/// since not all controllers report the details for all signals.
 #[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
 struct RawSingleSignal<'a> {
 	#[serde(rename = "_title")]
 	pub title: &'a str,
 
+	pub name: &'a str,
+
 	#[serde(rename = "type")]
 	pub kind: SignalKind,
 
 	pub category: &'a str,
 
 	pub lvalue: &'a str,
+
+	#[serde(default)]
+	pub lstate: Option<SimulationState>,
+
+	#[serde(rename = "ltime-sec")]
+	#[serde(default, deserialize_with = "deserialize_option_through_str")]
+	pub ltime_sec: Option<usize>,
+
+	#[serde(rename = "ltime-microsec")]
+	#[serde(default, deserialize_with = "deserialize_option_through_str")]
+	pub ltime_microsec: Option<usize>,
+
+	#[serde(default)]
+	pub pvalue: Option<&'a str>,
+
+	#[serde(default)]
+	pub phstate: Option<&'a str>,
+
+	#[serde(rename = "ptime-sec")]
+	#[serde(default, deserialize_with = "deserialize_option_through_str")]
+	pub ptime_sec: Option<usize>,
+
+	#[serde(rename = "ptime-microsec")]
+	#[serde(default, deserialize_with = "deserialize_option_through_str")]
+	pub ptime_microsec: Option<usize>,
+
+	#[serde(default)]
+	pub unitnm: Option<&'a str>,
+
+	#[serde(default, deserialize_with = "deserialize_option_through_str")]
+	pub quality: Option<usize>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Deserialize)]
+/// A signal value that may be encoded as JSON number or as string.
+///
+/// The value is kept in its raw form until the signal type is known,
+/// so that integers are never rounded through a floating point value.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+	Number(serde_json::Number),
+	String(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
 struct RawListSignal<'a> {
 	#[serde(rename = "_title")]
 	pub title: &'a str,
@@ -104,10 +415,13 @@ struct RawListSignal<'a> {
 
 	pub category: &'a str,
 
-	pub lvalue: f64,
+	pub lvalue: RawValue,
+
+	pub lstate: SimulationState,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// An I/O signal with its current value.
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Signal {
 	pub title: String,
 
@@ -116,84 +430,197 @@ pub struct Signal {
 	pub category: String,
 
 	pub lvalue: SignalValue,
+
+	/// Whether the logical value is simulated.
+	pub lstate: SimulationState,
 }
 
-impl Signal {
-	fn from_single_raw(raw: RawSingleSignal) -> serde_json::Result<Self> {
-		use serde::de::Unexpected;
-		use serde::de::Error;
-
-		// Parse value depending on signal type.
-		let value = match raw.kind {
-			SignalKind::AnalogInput | SignalKind::AnalogOutput => {
-				raw.lvalue.parse::<f64>()
-					.map(SignalValue::Analog)
-					.map_err(|_| Error::invalid_type(Unexpected::Str(raw.lvalue), &"floating-point value"))
-			},
-			SignalKind::DigitalInput | SignalKind::DigitalOutput => {
-				match raw.lvalue {
-					"1" => Ok(SignalValue::Binary(true)),
-					"0" => Ok(SignalValue::Binary(false)),
-					_   => Err(Error::invalid_type(Unexpected::Str(raw.lvalue), &"1 or 0"))
-				}
-			},
-			SignalKind::GroupInput | SignalKind::GroupOutput => {
-				raw.lvalue.parse::<u64>()
-					.map(SignalValue::Group)
-					.map_err(|_| Error::invalid_type(Unexpected::Str(raw.lvalue), &"integer"))
-			},
-		}?;
+/// All details of a single signal, as reported by the controller.
+///
+/// When serialized, timestamps are written as microseconds since the Unix epoch.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SignalDetails {
+	pub title: String,
 
-		Ok(Signal {
+	pub name: String,
+
+	pub kind: SignalKind,
+
+	pub category: String,
+
+	/// The logical value of the signal, as seen by RAPID programs.
+	pub lvalue: SignalValue,
+
+	/// Whether the logical value is simulated.
+	///
+	/// If the controller does not report the state, the signal is assumed to be not simulated.
+	pub lstate: SimulationState,
+
+	/// The time the logical value last changed, according to the controller clock.
+	#[serde(serialize_with = "serialize_timestamp")]
+	pub ltime: Option<SystemTime>,
+
+	/// The physical value of the signal, as seen on the I/O device.
+	pub pvalue: Option<SignalValue>,
+
+	/// The state of the physical signal, like `valid`, or empty if not reported.
+	pub phstate: String,
+
+	/// The time the physical value last changed, according to the controller clock.
+	#[serde(serialize_with = "serialize_timestamp")]
+	pub ptime: Option<SystemTime>,
+
+	/// The name of the I/O device the signal is mapped to, or empty if not reported.
+	pub unit: String,
+
+	/// The signal quality reported by the I/O device.
+	pub quality: Option<usize>,
+}
+
+impl SignalDetails {
+	fn from_raw(raw: RawSingleSignal) -> serde_json::Result<Self> {
+		Ok(Self {
 			title: raw.title.into(),
+			name: raw.name.into(),
 			kind: raw.kind,
 			category: raw.category.into(),
-			lvalue: value,
+			lvalue: parse_value_str(raw.kind, raw.lvalue)?,
+			lstate: raw.lstate.unwrap_or(SimulationState::NotSimulated),
+			ltime: raw.ltime_sec.map(|x| to_system_time(x, raw.ltime_microsec.unwrap_or(0))),
+			pvalue: raw.pvalue.map(|x| parse_value_str(raw.kind, x)).transpose()?,
+			phstate: raw.phstate.unwrap_or_default().into(),
+			ptime: raw.ptime_sec.map(|x| to_system_time(x, raw.ptime_microsec.unwrap_or(0))),
+			unit: raw.unitnm.unwrap_or_default().into(),
+			quality: raw.quality,
 		})
 	}
+}
+
+impl From<SignalDetails> for Signal {
+	fn from(details: SignalDetails) -> Self {
+		Self {
+			title: details.title,
+			kind: details.kind,
+			category: details.category,
+			lvalue: details.lvalue,
+			lstate: details.lstate,
+		}
+	}
+}
+
+/// Serialize a timestamp as microseconds since the Unix epoch.
+fn serialize_timestamp<S: serde::Serializer>(time: &Option<SystemTime>, serializer: S) -> Result<S::Ok, S::Error> {
+	let micros = time
+		.and_then(|x| x.duration_since(SystemTime::UNIX_EPOCH).ok())
+		.map(|x| u64::try_from(x.as_micros()).unwrap_or(u64::MAX));
+	micros.serialize(serializer)
+}
+
+/// Convert a timestamp in seconds and microseconds since the Unix epoch.
+fn to_system_time(seconds: usize, microseconds: usize) -> SystemTime {
+	SystemTime::UNIX_EPOCH + Duration::from_secs(seconds as u64) + Duration::from_micros(microseconds as u64)
+}
 
+impl Signal {
 	fn from_list_raw(raw: RawListSignal) -> serde_json::Result<Self> {
-		use serde::de::Unexpected;
-		use serde::de::Error;
-
-		// Parse value depending on signal type.
-		let value = match raw.kind {
-			SignalKind::AnalogInput | SignalKind::AnalogOutput => Ok(SignalValue::Analog(raw.lvalue)),
-			SignalKind::DigitalInput | SignalKind::DigitalOutput => {
-				if raw.lvalue == 1.0 {
-					Ok(SignalValue::Binary(true))
-				} else if raw.lvalue == 0.0 {
-					Ok(SignalValue::Binary(false))
-				} else {
-					Err(Error::invalid_type(Unexpected::Float(raw.lvalue), &"1 or 0"))
-				}
-			},
-			SignalKind::GroupInput | SignalKind::GroupOutput => {
-				// TODO: Is this safe? What's the maximum number of signals in a group,
-				// and do they fit lossless in a f64?
-				Ok(SignalValue::Group(raw.lvalue as u64))
-			},
-		}?;
+		let value = match &raw.lvalue {
+			RawValue::Number(x) => parse_value_number(raw.kind, x)?,
+			RawValue::String(x) => parse_value_str(raw.kind, x)?,
+		};
 
 		Ok(Signal {
 			title: raw.title.into(),
 			kind: raw.kind,
 			category: raw.category.into(),
 			lvalue: value,
+			lstate: raw.lstate,
 		})
 	}
 }
 
-pub fn parse_one(data: &[u8]) -> serde_json::Result<Signal> {
+/// Parse a signal value from a JSON number, depending on the signal type.
+///
+/// Group values must be non-negative integers that fit in a `u64` without loss of precision.
+fn parse_value_number(kind: SignalKind, value: &serde_json::Number) -> serde_json::Result<SignalValue> {
+	use serde::de::Unexpected;
+	use serde::de::Error;
+
+	/// 2^53, the first integer that an `f64` shares with a rounded neighbour.
+	///
+	/// Both 2^53 and 2^53 + 1 parse to this value, so only floats below it are exact.
+	const MAX_EXACT_F64: f64 = 9007199254740992.0;
+
+	let unexpected = match (value.as_u64(), value.as_i64(), value.as_f64()) {
+		(Some(x), _, _) => Unexpected::Unsigned(x),
+		(_, Some(x), _) => Unexpected::Signed(x),
+		(_, _, Some(x)) => Unexpected::Float(x),
+		_ => Unexpected::Other("number"),
+	};
+
+	match kind {
+		SignalKind::AnalogInput | SignalKind::AnalogOutput => {
+			value.as_f64()
+				.map(SignalValue::Analog)
+				.ok_or_else(|| Error::invalid_value(unexpected, &"floating-point value"))
+		},
+		SignalKind::DigitalInput | SignalKind::DigitalOutput => {
+			let value = value.as_f64();
+			if value == Some(1.0) {
+				Ok(SignalValue::Binary(true))
+			} else if value == Some(0.0) {
+				Ok(SignalValue::Binary(false))
+			} else {
+				Err(Error::invalid_value(unexpected, &"1 or 0"))
+			}
+		},
+		SignalKind::GroupInput | SignalKind::GroupOutput => {
+			if let Some(x) = value.as_u64() {
+				return Ok(SignalValue::Group(x));
+			}
+			// Integral floats are only accepted if they can not have been rounded.
+			// This relies on the `float_roundtrip` feature of serde_json for correctly rounded parsing.
+			match value.as_f64() {
+				Some(x) if x.fract() == 0.0 && (0.0..MAX_EXACT_F64).contains(&x) => Ok(SignalValue::Group(x as u64)),
+				_ => Err(Error::invalid_value(unexpected, &"unsigned integer that fits in 64 bits")),
+			}
+		},
+	}
+}
+
+/// Parse a signal value from a string, depending on the signal type.
+pub fn parse_value_str(kind: SignalKind, value: &str) -> serde_json::Result<SignalValue> {
+	use serde::de::Unexpected;
+	use serde::de::Error;
+
+	match kind {
+		SignalKind::AnalogInput | SignalKind::AnalogOutput => {
+			value.parse::<f64>()
+				.map(SignalValue::Analog)
+				.map_err(|_| Error::invalid_type(Unexpected::Str(value), &"floating-point value"))
+		},
+		SignalKind::DigitalInput | SignalKind::DigitalOutput => {
+			match value {
+				"1" => Ok(SignalValue::Binary(true)),
+				"0" => Ok(SignalValue::Binary(false)),
+				_   => Err(Error::invalid_type(Unexpected::Str(value), &"1 or 0"))
+			}
+		},
+		SignalKind::GroupInput | SignalKind::GroupOutput => {
+			value.parse::<u64>()
+				.map(SignalValue::Group)
+				.map_err(|_| Error::invalid_value(Unexpected::Str(value), &"unsigned integer that fits in 64 bits"))
+		},
+	}
+}
+
+pub fn parse_one(data: &[u8]) -> serde_json::Result<SignalDetails> {
 	super::parse_one::<RawSingleSignal>(data)
-		.and_then(Signal::from_single_raw)
+		.and_then(SignalDetails::from_raw)
 }
 
-pub fn parse_list(data: &[u8]) -> serde_json::Result<Vec<Signal>> {
-	super::parse_vec::<RawListSignal>(data)?
-		.into_iter()
-		.map(Signal::from_list_raw)
-		.collect()
+pub fn parse_list_page(data: &[u8]) -> serde_json::Result<super::Page<Signal>> {
+	super::parse_page::<RawListSignal>(data)?
+		.try_map(Signal::from_list_raw)
 }
 
 #[cfg(test)]
@@ -204,8 +631,88 @@ mod test {
 
 	#[test]
 	fn test_parse_signals() {
-		let parsed = parse_list(include_bytes!("../../../samples/signals.json"));
+		let parsed = parse_list_page(include_bytes!("../../../samples/signals.json"));
 		assert!(let Ok(_) = &parsed);
+		check!(parsed.unwrap().items.iter().all(|x| x.lstate == SimulationState::NotSimulated));
+	}
+
+	#[test]
+	fn test_signal_query_string() {
+		check!(SignalQuery::new().query_string(100) == "start=0&limit=100");
+		check!(SignalQuery::new().category("safety").query_string(100) == "category=safety&start=0&limit=100");
+		check!(SignalQuery::new().kind(SignalKind::DigitalInput).device("DRV 1").start(10).limit(5).query_string(100) == "type=DI&device=DRV%201&start=10&limit=5");
+	}
+
+	#[test]
+	fn test_set_options_form_data() {
+		check!(SetOptions::default().form_data(SignalValue::Group(3)) == "lvalue=3&mode=value");
+
+		let options = SetOptions { mode: SetMode::Pulse, pulses: 2, active_ms: 200, passive_ms: 100, ..Default::default() };
+		check!(options.form_data(SignalValue::Binary(true)) == "lvalue=1&mode=pulse&Pulses=2&ActivePulse=200&PassivePulse=100");
+
+		let options = SetOptions { mode: SetMode::Delay, delay_ms: 500, ..Default::default() };
+		check!(options.form_data(SignalValue::Binary(false)) == "lvalue=0&mode=delay&Delay=500");
+	}
+
+	#[test]
+	fn test_parse_group_value() {
+		let number = |x: &str| x.parse::<serde_json::Number>().unwrap();
+		check!(let Ok(SignalValue::Group(u64::MAX)) = parse_value_number(SignalKind::GroupInput, &number("18446744073709551615")));
+		check!(let Ok(SignalValue::Group(9007199254740993)) = parse_value_number(SignalKind::GroupInput, &number("9007199254740993")));
+		check!(let Ok(SignalValue::Group(12)) = parse_value_number(SignalKind::GroupInput, &number("12.0")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("1.5")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("-1")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("18446744073709551616")));
+		check!(let Ok(SignalValue::Group(9007199254740991)) = parse_value_number(SignalKind::GroupInput, &number("9007199254740991.0")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("9007199254740992.0")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("9007199254740993.0")));
+		check!(let Err(_) = parse_value_number(SignalKind::GroupInput, &number("9007199254740994.0")));
+		check!(let Ok(SignalValue::Group(u64::MAX)) = parse_value_str(SignalKind::GroupInput, "18446744073709551615"));
+		check!(let Err(_) = parse_value_str(SignalKind::GroupInput, "18446744073709551616"));
+	}
+
+	#[test]
+	fn test_signal_value_bits() {
+		let value = SignalValue::Group(0b1010);
+		check!(value.bit(0) == Some(false));
+		check!(value.bit(1) == Some(true));
+		check!(value.bit(3) == Some(true));
+		check!(value.bit(63) == Some(false));
+		check!(value.bit(64) == None);
+		check!(value.bits() == Some(0b1010));
+		check!(value.with_bit(0, true) == Some(SignalValue::Group(0b1011)));
+		check!(value.with_bit(1, false) == Some(SignalValue::Group(0b1000)));
+		check!(value.with_bit(64, true) == None);
+
+		check!(SignalValue::Binary(true).bit(0) == Some(true));
+		check!(SignalValue::Binary(true).bit(1) == None);
+		check!(SignalValue::Binary(true).bits() == Some(1));
+		check!(SignalValue::Analog(1.0).bit(0) == None);
+		check!(SignalValue::Analog(1.0).bits() == None);
+	}
+
+	#[test]
+	fn test_serialize_signal() {
+		let signal = Signal {
+			title: "Local/DRV_1/GO1".into(),
+			kind: SignalKind::GroupOutput,
+			category: "".into(),
+			lvalue: SignalValue::Group(12),
+			lstate: SimulationState::Simulated,
+		};
+		check!(serde_json::to_string(&signal).unwrap() == r#"{"title":"Local/DRV_1/GO1","kind":"GO","category":"","lvalue":12,"lstate":"simulated"}"#);
+		check!(serde_json::to_string(&SignalValue::Binary(true)).unwrap() == "true");
+		check!(serde_json::to_string(&SignalValue::Analog(0.5)).unwrap() == "0.5");
+	}
+
+	#[test]
+	fn test_serialize_signal_details() {
+		let mut signal = parse_one(include_bytes!("../../../samples/good_signal.json")).unwrap();
+		signal.ptime = None;
+		check!(serde_json::to_string(&signal).unwrap() == concat!(
+			r#"{"title":"Local/PANEL/SS2","name":"SS2","kind":"DI","category":"safety","lvalue":true,"lstate":"not simulated","#,
+			r#""ltime":1579255059538338,"pvalue":true,"phstate":"valid","ptime":null,"unit":"PANEL","quality":1}"#,
+		));
 	}
 
 	#[test]
@@ -220,8 +727,38 @@ mod test {
 		let parsed = parsed.unwrap();
 
 		check!(parsed.title    == "Local/PANEL/SS2");
+		check!(parsed.name     == "SS2");
 		check!(parsed.category == "safety");
 		check!(parsed.kind     == SignalKind::DigitalInput);
 		check!(parsed.lvalue   == SignalValue::Binary(true));
+		check!(parsed.lstate   == SimulationState::NotSimulated);
+		check!(parsed.ltime    == Some(SystemTime::UNIX_EPOCH + Duration::from_micros(1_579_255_059_538_338)));
+		check!(parsed.pvalue   == Some(SignalValue::Binary(true)));
+		check!(parsed.phstate  == "valid");
+		check!(parsed.ptime    == parsed.ltime);
+		check!(parsed.unit     == "PANEL");
+		check!(parsed.quality  == Some(1));
+	}
+
+	#[test]
+	fn test_parse_minimal_signal() {
+		let data = br#"{
+			"_links": {},
+			"_embedded": { "_state": [
+				{ "_type": "ios-signal", "_title": "Local/DRV_1/GO1", "name": "GO1", "type": "GO", "category": "", "lvalue": "12" }
+			] }
+		}"#;
+		let parsed = parse_one(data);
+		assert!(let Ok(_) = &parsed);
+		let parsed = parsed.unwrap();
+
+		check!(parsed.lvalue  == SignalValue::Group(12));
+		check!(parsed.lstate  == SimulationState::NotSimulated);
+		check!(parsed.ltime   == None);
+		check!(parsed.pvalue  == None);
+		check!(parsed.phstate == "");
+		check!(parsed.ptime   == None);
+		check!(parsed.unit    == "");
+		check!(parsed.quality == None);
 	}
 }
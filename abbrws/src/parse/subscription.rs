@@ -0,0 +1,104 @@
+//! Parsing of subscription events.
+//!
+//! Unlike the rest of RWS, subscription events are only available as XHTML.
+//! We only need a handful of values from them,
+//! so they are extracted with a simple scanner instead of a full XML parser.
+
+/// A change event for a single signal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignalEvent {
+	/// The title of the signal, as used in the signal URL.
+	pub title: String,
+
+	/// The new logical value of the signal, still encoded as string.
+	pub lvalue: String,
+}
+
+/// Parse all signal state events from a subscription event message.
+///
+/// Unrecognized elements are ignored.
+pub fn parse_signal_events(data: &str) -> Vec<SignalEvent> {
+	let mut events = Vec::new();
+	let mut remaining = data;
+
+	while let Some(start) = remaining.find("<li ") {
+		remaining = &remaining[start..];
+		let end = remaining.find("</li>").unwrap_or(remaining.len());
+		let item = &remaining[..end];
+		remaining = &remaining[end..];
+
+		let tag = match item.find('>') {
+			Some(x) => &item[..x],
+			None => continue,
+		};
+
+		if get_attribute(tag, "class") != Some("ios-signalstate-ev") {
+			continue;
+		}
+
+		let title = match get_attribute(tag, "title") {
+			Some(x) => x,
+			None => continue,
+		};
+
+		let lvalue = match get_span(item, "lvalue") {
+			Some(x) => x,
+			None => continue,
+		};
+
+		events.push(SignalEvent {
+			title: unescape(title),
+			lvalue: unescape(lvalue.trim()),
+		});
+	}
+
+	events
+}
+
+/// Get the value of an attribute from an opening tag.
+fn get_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+	let pattern = format!(" {}=\"", name);
+	let start = tag.find(&pattern)? + pattern.len();
+	let len = tag[start..].find('"')?;
+	Some(&tag[start..start + len])
+}
+
+/// Get the contents of a `<span class="...">` element.
+fn get_span<'a>(data: &'a str, class: &str) -> Option<&'a str> {
+	let pattern = format!("<span class=\"{}\">", class);
+	let start = data.find(&pattern)? + pattern.len();
+	let len = data[start..].find("</span>")?;
+	Some(&data[start..start + len])
+}
+
+/// Replace the predefined XML entities with the characters they represent.
+fn unescape(data: &str) -> String {
+	data
+		.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&apos;", "'")
+		.replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_parse_signal_events() {
+		let data = std::str::from_utf8(include_bytes!("../../../samples/signal_event.xml")).unwrap();
+		let events = parse_signal_events(data);
+		assert!(events == vec![
+			SignalEvent { title: "Local/PANEL/SS2".into(), lvalue: "0".into() },
+			SignalEvent { title: "Local/DRV_1/DRV1K1".into(), lvalue: "1".into() },
+		]);
+	}
+
+	#[test]
+	fn test_parse_signal_events_escaped() {
+		let data = r#"<li class="ios-signalstate-ev" title="a&amp;b"><span class="lvalue">1</span></li>"#;
+		assert!(parse_signal_events(data) == vec![SignalEvent { title: "a&b".into(), lvalue: "1".into() }]);
+	}
+}
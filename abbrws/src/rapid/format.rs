@@ -0,0 +1,228 @@
+use super::ExtJoint;
+use super::FormatRapidValueError;
+use super::RapidValue;
+use super::Spelling;
+use super::lexer::Lexer;
+use super::lexer::Token;
+
+impl RapidValue {
+	/// Format the value as a RAPID literal.
+	///
+	/// Unlike [`ToString::to_string`], this returns an error instead of panicking
+	/// if the value contains an infinite or NaN number.
+	pub fn to_literal(&self) -> Result<String, FormatRapidValueError> {
+		match self.non_finite() {
+			Some(value) => Err(FormatRapidValueError { value }),
+			None => Ok(self.to_string()),
+		}
+	}
+
+	/// Find the first infinite or NaN number in the value.
+	fn non_finite(&self) -> Option<f64> {
+		let nums = match self {
+			Self::Num(x, _) => vec![*x],
+			Self::Bool(_) | Self::String(..) => Vec::new(),
+			Self::RobTarget(x) => vec![
+				x.trans.x, x.trans.y, x.trans.z,
+				x.rot.q1, x.rot.q2, x.rot.q3, x.rot.q4,
+				x.robconf.cf1, x.robconf.cf4, x.robconf.cf6, x.robconf.cfx,
+				x.extax.eax_a, x.extax.eax_b, x.extax.eax_c, x.extax.eax_d, x.extax.eax_e, x.extax.eax_f,
+			],
+			Self::JointTarget(x) => vec![
+				x.robax.rax_1, x.robax.rax_2, x.robax.rax_3, x.robax.rax_4, x.robax.rax_5, x.robax.rax_6,
+				x.extax.eax_a, x.extax.eax_b, x.extax.eax_c, x.extax.eax_d, x.extax.eax_e, x.extax.eax_f,
+			],
+			Self::Pose(x) => vec![x.trans.x, x.trans.y, x.trans.z, x.rot.q1, x.rot.q2, x.rot.q3, x.rot.q4],
+			Self::Record(fields) | Self::Array(fields) => return fields.iter().find_map(Self::non_finite),
+		};
+		nums.into_iter().find(|x| !x.is_finite())
+	}
+}
+
+/// Formats the value as a RAPID literal.
+///
+/// Formatting fails for values that contain an infinite or NaN number,
+/// since RAPID literals can not represent those. Use [`RapidValue::to_literal`] to handle that case.
+impl std::fmt::Display for RapidValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Num(x, spelling) => match original(spelling, Token::Num(*x)) {
+				Some(raw) => f.write_str(raw),
+				None => write_num(f, *x),
+			},
+			Self::Bool(x) => write!(f, "{}", if *x { "TRUE" } else { "FALSE" }),
+			Self::String(x, spelling) => match original(spelling, Token::String(x.clone())) {
+				Some(raw) => f.write_str(raw),
+				None => write_string(f, x),
+			},
+			Self::RobTarget(x) => {
+				write!(f, "[")?;
+				write_nums(f, &[x.trans.x, x.trans.y, x.trans.z])?;
+				write!(f, ",")?;
+				write_nums(f, &[x.rot.q1, x.rot.q2, x.rot.q3, x.rot.q4])?;
+				write!(f, ",")?;
+				write_nums(f, &[x.robconf.cf1, x.robconf.cf4, x.robconf.cf6, x.robconf.cfx])?;
+				write!(f, ",")?;
+				write_extjoint(f, &x.extax)?;
+				write!(f, "]")
+			},
+			Self::JointTarget(x) => {
+				write!(f, "[")?;
+				write_nums(f, &[x.robax.rax_1, x.robax.rax_2, x.robax.rax_3, x.robax.rax_4, x.robax.rax_5, x.robax.rax_6])?;
+				write!(f, ",")?;
+				write_extjoint(f, &x.extax)?;
+				write!(f, "]")
+			},
+			Self::Pose(x) => {
+				write!(f, "[")?;
+				write_nums(f, &[x.trans.x, x.trans.y, x.trans.z])?;
+				write!(f, ",")?;
+				write_nums(f, &[x.rot.q1, x.rot.q2, x.rot.q3, x.rot.q4])?;
+				write!(f, "]")
+			},
+			Self::Record(fields) | Self::Array(fields) => {
+				write!(f, "[")?;
+				for (i, field) in fields.iter().enumerate() {
+					if i > 0 {
+						write!(f, ",")?;
+					}
+					write!(f, "{}", field)?;
+				}
+				write!(f, "]")
+			},
+		}
+	}
+}
+
+/// Get the original spelling of a literal, if it still lexes to the same token.
+///
+/// A parsed value may have been modified afterwards, in which case the spelling is stale.
+fn original(spelling: &Spelling, token: Token) -> Option<&str> {
+	let raw = spelling.as_str()?;
+	let mut lexer = Lexer::new(raw);
+	match (lexer.next_token(), lexer.next_token()) {
+		(Ok(Some((_, lexed))), Ok(None)) if lexed == token => Some(raw),
+		_ => None,
+	}
+}
+
+/// Write a number in the shortest form that parses back to the exact same value.
+///
+/// This gives `500` for 500 and `9E9` for the 9E9 sentinel value.
+/// Infinite and NaN numbers have no literal, so they give an error.
+fn write_num(f: &mut std::fmt::Formatter, value: f64) -> std::fmt::Result {
+	if !value.is_finite() {
+		return Err(std::fmt::Error);
+	}
+	let plain = format!("{}", value);
+	let exponent = format!("{:E}", value);
+	if exponent.len() < plain.len() {
+		f.write_str(&exponent)
+	} else {
+		f.write_str(&plain)
+	}
+}
+
+/// Write a string literal, escaping quotes, backslashes and non-printable characters.
+fn write_string(f: &mut std::fmt::Formatter, value: &str) -> std::fmt::Result {
+	write!(f, "\"")?;
+	for c in value.chars() {
+		match c {
+			'"' => write!(f, "\"\"")?,
+			'\\' => write!(f, "\\\\")?,
+			c if c.is_ascii_control() => write!(f, "\\{:02X}", c as u8)?,
+			c => write!(f, "{}", c)?,
+		}
+	}
+	write!(f, "\"")
+}
+
+fn write_nums(f: &mut std::fmt::Formatter, values: &[f64]) -> std::fmt::Result {
+	write!(f, "[")?;
+	for (i, value) in values.iter().enumerate() {
+		if i > 0 {
+			write!(f, ",")?;
+		}
+		write_num(f, *value)?;
+	}
+	write!(f, "]")
+}
+
+fn write_extjoint(f: &mut std::fmt::Formatter, extax: &ExtJoint) -> std::fmt::Result {
+	write_nums(f, &[extax.eax_a, extax.eax_b, extax.eax_c, extax.eax_d, extax.eax_e, extax.eax_f])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+	use assert2::check;
+
+	fn round_trip(input: &str) -> String {
+		input.parse::<RapidValue>().unwrap().to_string()
+	}
+
+	#[test]
+	fn test_round_trip() {
+		check!(round_trip("500") == "500");
+		check!(round_trip("-0.7071068") == "-0.7071068");
+		check!(round_trip("9E9") == "9E9");
+		check!(round_trip("1.5E-6") == "1.5E-6");
+		check!(round_trip("TRUE") == "TRUE");
+		check!(round_trip(r#""say ""hi"" \\ \0A""#) == r#""say ""hi"" \\ \0A""#);
+		check!(round_trip("[[500,0,600],[1,0,0,0],[0,0,0,0],[9E9,9E9,9E9,9E9,9E9,9E9]]") == "[[500,0,600],[1,0,0,0],[0,0,0,0],[9E9,9E9,9E9,9E9,9E9,9E9]]");
+	}
+
+	#[test]
+	fn test_round_trip_values() {
+		for value in [0.0, -0.0, 1.0, -1.0, 0.1, 1.0 / 3.0, 123456789.0, 9E9, 1E-30, f64::MAX, f64::MIN_POSITIVE] {
+			let formatted = RapidValue::num(value).to_string();
+			assert!(formatted.parse() == Ok(RapidValue::num(value)), "formatted: {}", formatted);
+		}
+	}
+
+	#[test]
+	fn test_non_finite() {
+		for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+			let error = RapidValue::num(value).to_literal().unwrap_err();
+			assert!(error.value.to_bits() == value.to_bits());
+
+			let pose = RapidValue::Pose(crate::Pose {
+				trans: crate::Pos { x: 0.0, y: value, z: 0.0 },
+				rot: crate::Orient { q1: 1.0, q2: 0.0, q3: 0.0, q4: 0.0 },
+			});
+			assert!(let Err(_) = RapidValue::Array(vec![RapidValue::num(1.0), pose.clone()]).to_literal());
+
+			use std::fmt::Write;
+			assert!(let Err(_) = write!(String::new(), "{}", pose));
+		}
+
+		// Numbers that overflow to infinity are rejected by the parser, so every parsed value can be formatted again.
+		assert!(let Err(_) = "1E999".parse::<RapidValue>());
+		assert!(let Err(_) = "[-1E999]".parse::<RapidValue>());
+		assert!(let Ok(_) = "[1,2]".parse::<RapidValue>().unwrap().to_literal());
+	}
+
+	#[test]
+	fn test_keep_spelling() {
+		check!(round_trip("9E+09") == "9E+09");
+		check!(round_trip("+1.50") == "+1.50");
+		check!(round_trip(r#""\41""#) == r#""\41""#);
+		check!(round_trip("[ 1 , 9e+09 ]") == "[1,9e+09]");
+	}
+
+	#[test]
+	fn test_stale_spelling() {
+		let mut value : RapidValue = "9E+09".parse().unwrap();
+		if let RapidValue::Num(x, _) = &mut value {
+			*x = 1.0;
+		}
+		check!(value.to_string() == "1");
+
+		let mut value : RapidValue = r#""\41""#.parse().unwrap();
+		if let RapidValue::String(x, _) = &mut value {
+			x.push('B');
+		}
+		check!(value.to_string() == r#""AB""#);
+	}
+}
@@ -0,0 +1,204 @@
+use super::ParseRapidValueError;
+
+/// A token in a RAPID literal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+	OpenBracket,
+	CloseBracket,
+	Comma,
+	Num(f64),
+	Bool(bool),
+	String(String),
+}
+
+/// Lexer for RAPID literals.
+///
+/// The lexer yields tokens together with their byte offset in the input.
+pub struct Lexer<'a> {
+	input: &'a str,
+	position: usize,
+}
+
+impl<'a> Lexer<'a> {
+	pub fn new(input: &'a str) -> Self {
+		Self { input, position: 0 }
+	}
+
+	/// The byte offset of the next unprocessed character.
+	pub fn position(&self) -> usize {
+		self.position
+	}
+
+	fn remaining(&self) -> &'a str {
+		&self.input[self.position..]
+	}
+
+	fn error(&self, position: usize, message: impl Into<String>) -> ParseRapidValueError {
+		ParseRapidValueError { position, message: message.into() }
+	}
+
+	fn skip_whitespace(&mut self) {
+		let remaining = self.remaining();
+		self.position += remaining.len() - remaining.trim_start().len();
+	}
+
+	/// Get the next token, or `None` at the end of the input.
+	pub fn next_token(&mut self) -> Result<Option<(usize, Token)>, ParseRapidValueError> {
+		self.skip_whitespace();
+		let start = self.position;
+		let token = match self.remaining().chars().next() {
+			None => return Ok(None),
+			Some('[') => {
+				self.position += 1;
+				Token::OpenBracket
+			},
+			Some(']') => {
+				self.position += 1;
+				Token::CloseBracket
+			},
+			Some(',') => {
+				self.position += 1;
+				Token::Comma
+			},
+			Some('"') => self.lex_string()?,
+			Some(c) if c == '-' || c == '+' || c == '.' || c.is_ascii_digit() => self.lex_num()?,
+			Some(c) if c.is_ascii_alphabetic() => self.lex_word()?,
+			Some(c) => return Err(self.error(start, format!("unexpected character {:?}", c))),
+		};
+		Ok(Some((start, token)))
+	}
+
+	/// Lex a string literal.
+	///
+	/// Quotes and backslashes are escaped by doubling them.
+	/// Other characters may be written as a backslash followed by two hexadecimal digits.
+	fn lex_string(&mut self) -> Result<Token, ParseRapidValueError> {
+		let start = self.position;
+		self.position += 1;
+		let mut value = String::new();
+		loop {
+			let remaining = self.remaining();
+			let mut chars = remaining.chars();
+			match (chars.next(), chars.next()) {
+				(Some('"'), Some('"')) => {
+					value.push('"');
+					self.position += 2;
+				},
+				(Some('"'), _) => {
+					self.position += 1;
+					return Ok(Token::String(value));
+				},
+				(Some('\\'), Some('\\')) => {
+					value.push('\\');
+					self.position += 2;
+				},
+				(Some('\\'), _) => {
+					let code = remaining.get(1..3)
+						.and_then(|x| u8::from_str_radix(x, 16).ok())
+						.ok_or_else(|| self.error(self.position, "invalid escape sequence in string"))?;
+					value.push(char::from(code));
+					self.position += 3;
+				},
+				(Some(c), _) => {
+					value.push(c);
+					self.position += c.len_utf8();
+				},
+				(None, _) => return Err(self.error(start, "unterminated string")),
+			}
+		}
+	}
+
+	/// Lex a numeric literal, like `-1`, `0.5`, `9E9` or `9E+09`.
+	///
+	/// Numbers that are too large to represent are rejected, rather than lexed as infinity.
+	fn lex_num(&mut self) -> Result<Token, ParseRapidValueError> {
+		let start = self.position;
+		let bytes = self.remaining().as_bytes();
+		let skip = |mut len: usize, accept: fn(u8) -> bool| {
+			while len < bytes.len() && accept(bytes[len]) {
+				len += 1;
+			}
+			len
+		};
+
+		let mut len = 0;
+		if bytes[0] == b'-' || bytes[0] == b'+' {
+			len += 1;
+		}
+		len = skip(len, |c| c.is_ascii_digit() || c == b'.');
+		if len < bytes.len() && (bytes[len] == b'E' || bytes[len] == b'e') {
+			len += 1;
+			if len < bytes.len() && (bytes[len] == b'-' || bytes[len] == b'+') {
+				len += 1;
+			}
+			len = skip(len, |c| c.is_ascii_digit());
+		}
+
+		let text = &self.remaining()[..len];
+		let value : f64 = text.parse().map_err(|_| self.error(start, format!("invalid number: {:?}", text)))?;
+		if !value.is_finite() {
+			return Err(self.error(start, format!("number out of range: {:?}", text)));
+		}
+		self.position += len;
+		Ok(Token::Num(value))
+	}
+
+	/// Lex a keyword: `TRUE` or `FALSE`.
+	fn lex_word(&mut self) -> Result<Token, ParseRapidValueError> {
+		let start = self.position;
+		let remaining = self.remaining();
+		let len = remaining.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(remaining.len());
+		let word = &remaining[..len];
+		let token = if word.eq_ignore_ascii_case("TRUE") {
+			Token::Bool(true)
+		} else if word.eq_ignore_ascii_case("FALSE") {
+			Token::Bool(false)
+		} else {
+			return Err(self.error(start, format!("unexpected identifier {:?}", word)));
+		};
+		self.position += len;
+		Ok(token)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn tokens(input: &str) -> Result<Vec<Token>, ParseRapidValueError> {
+		let mut lexer = Lexer::new(input);
+		let mut tokens = Vec::new();
+		while let Some((_, token)) = lexer.next_token()? {
+			tokens.push(token);
+		}
+		Ok(tokens)
+	}
+
+	#[test]
+	fn test_lex() {
+		assert!(let Ok(_) = tokens(""));
+		assert!(tokens("[1, -2.5,9E9, 9E+09]").unwrap() == [
+			Token::OpenBracket,
+			Token::Num(1.0),
+			Token::Comma,
+			Token::Num(-2.5),
+			Token::Comma,
+			Token::Num(9E9),
+			Token::Comma,
+			Token::Num(9E9),
+			Token::CloseBracket,
+		]);
+		assert!(tokens("TRUE false").unwrap() == [Token::Bool(true), Token::Bool(false)]);
+		assert!(tokens(r#""a""b\\c\41""#).unwrap() == [Token::String(r#"a"b\cA"#.into())]);
+	}
+
+	#[test]
+	fn test_lex_errors() {
+		assert!(let Err(ParseRapidValueError { position: 0, .. }) = tokens(r#""abc"#));
+		assert!(let Err(ParseRapidValueError { position: 3, .. }) = tokens(r#"[1,aap]"#));
+		assert!(let Err(ParseRapidValueError { position: 1, .. }) = tokens(r#"[-]"#));
+		assert!(let Err(ParseRapidValueError { position: 1, .. }) = tokens(r#""\z""#));
+		assert!(let Err(ParseRapidValueError { position: 0, .. }) = tokens(r#"#"#));
+	}
+}
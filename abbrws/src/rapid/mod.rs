@@ -0,0 +1,57 @@
+//! Parsing and formatting of RAPID data literals.
+//!
+//! RWS reads and writes RAPID data as text literals,
+//! like `[[500,0,600],[1,0,0,0],[0,0,0,0],[9E9,9E9,9E9,9E9,9E9,9E9]]`.
+//! Without type information, such a literal is parsed into a tree of [`RapidValue::Record`] values.
+//!
+//! Formatting a value gives a literal that parses back to exactly the same value.
+//! Numbers and strings that were parsed are formatted with their original spelling, like `9E+09`.
+//! RAPID literals can not represent infinite or NaN numbers, so values containing those can not be formatted.
+
+mod format;
+mod lexer;
+mod parser;
+mod value;
+
+pub use value::*;
+
+/// Error that occurs when parsing a RAPID literal fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseRapidValueError {
+	/// The byte offset in the input where the error occurred.
+	pub position: usize,
+
+	/// A description of the error.
+	pub message: String,
+}
+
+impl std::error::Error for ParseRapidValueError {}
+
+impl std::fmt::Display for ParseRapidValueError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "invalid RAPID literal at position {}: {}", self.position, self.message)
+	}
+}
+
+/// Error that occurs when formatting a RAPID value with a number that has no RAPID literal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatRapidValueError {
+	/// The infinite or NaN number.
+	pub value: f64,
+}
+
+impl std::error::Error for FormatRapidValueError {}
+
+impl std::fmt::Display for FormatRapidValueError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "can not format {} as RAPID literal", self.value)
+	}
+}
+
+impl std::str::FromStr for RapidValue {
+	type Err = ParseRapidValueError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		parser::parse(input)
+	}
+}
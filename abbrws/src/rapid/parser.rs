@@ -0,0 +1,98 @@
+use super::ParseRapidValueError;
+use super::RapidValue;
+use super::Spelling;
+use super::lexer::Lexer;
+use super::lexer::Token;
+
+/// Parse a RAPID literal without any type information.
+///
+/// Aggregates are parsed as [`RapidValue::Record`].
+pub fn parse(input: &str) -> Result<RapidValue, ParseRapidValueError> {
+	let mut parser = Parser { input, lexer: Lexer::new(input) };
+	let value = parser.parse_value()?;
+	match parser.next()? {
+		None => Ok(value),
+		Some((position, _)) => Err(ParseRapidValueError { position, message: "expected end of input".into() }),
+	}
+}
+
+/// Recursive descent parser for RAPID literals.
+struct Parser<'a> {
+	input: &'a str,
+	lexer: Lexer<'a>,
+}
+
+impl Parser<'_> {
+	fn next(&mut self) -> Result<Option<(usize, Token)>, ParseRapidValueError> {
+		self.lexer.next_token()
+	}
+
+	/// Get the spelling of the token that was just lexed, starting at the given position.
+	fn spelling(&self, start: usize) -> Spelling {
+		Spelling::new(&self.input[start..self.lexer.position()])
+	}
+
+	fn expected(&self, position: Option<usize>, expected: &str) -> ParseRapidValueError {
+		ParseRapidValueError {
+			position: position.unwrap_or_else(|| self.lexer.position()),
+			message: format!("expected {}", expected),
+		}
+	}
+
+	fn parse_value(&mut self) -> Result<RapidValue, ParseRapidValueError> {
+		match self.next()? {
+			Some((start, Token::Num(x))) => Ok(RapidValue::Num(x, self.spelling(start))),
+			Some((_, Token::Bool(x))) => Ok(RapidValue::Bool(x)),
+			Some((start, Token::String(x))) => Ok(RapidValue::String(x, self.spelling(start))),
+			Some((_, Token::OpenBracket)) => self.parse_aggregate(),
+			Some((position, _)) => Err(self.expected(Some(position), "value")),
+			None => Err(self.expected(None, "value")),
+		}
+	}
+
+	/// Parse the rest of an aggregate after the opening bracket.
+	fn parse_aggregate(&mut self) -> Result<RapidValue, ParseRapidValueError> {
+		let mut fields = Vec::new();
+		loop {
+			fields.push(self.parse_value()?);
+			match self.next()? {
+				Some((_, Token::Comma)) => (),
+				Some((_, Token::CloseBracket)) => return Ok(RapidValue::Record(fields)),
+				Some((position, _)) => return Err(self.expected(Some(position), "',' or ']'")),
+				None => return Err(self.expected(None, "',' or ']'")),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_parse() {
+		assert!(parse("-1.5") == Ok(RapidValue::num(-1.5)));
+		assert!(parse(" 9E+09 ") == Ok(RapidValue::num(9E9)));
+		assert!(parse(" TRUE ") == Ok(RapidValue::Bool(true)));
+		assert!(parse(r#""say ""hi"" \\""#) == Ok(RapidValue::string(r#"say "hi" \"#)));
+		assert!(parse(r#"[1, "a", [FALSE, []]]"#).is_err());
+		assert!(parse(r#"[1, "a", [FALSE, [2]]]"#) == Ok(RapidValue::Record(vec![
+			RapidValue::num(1.0),
+			RapidValue::string("a"),
+			RapidValue::Record(vec![
+				RapidValue::Bool(false),
+				RapidValue::Record(vec![RapidValue::num(2.0)]),
+			]),
+		])));
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!(let Err(ParseRapidValueError { position: 4, .. }) = parse("[1,2"));
+		assert!(let Err(ParseRapidValueError { position: 2, .. }) = parse("1 2"));
+		assert!(let Err(ParseRapidValueError { position: 3, .. }) = parse("[1,,2]"));
+		assert!(let Err(ParseRapidValueError { position: 0, .. }) = parse("]"));
+		assert!(let Err(ParseRapidValueError { position: 0, .. }) = parse(""));
+	}
+}
@@ -0,0 +1,147 @@
+/// A position in 3D space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pos {
+	pub x: f64,
+	pub y: f64,
+	pub z: f64,
+}
+
+/// An orientation in the form of a quaternion.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Orient {
+	pub q1: f64,
+	pub q2: f64,
+	pub q3: f64,
+	pub q4: f64,
+}
+
+/// The axis configuration of a robot.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConfData {
+	pub cf1: f64,
+	pub cf4: f64,
+	pub cf6: f64,
+	pub cfx: f64,
+}
+
+/// The positions of the robot axes, in degrees.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RobJoint {
+	pub rax_1: f64,
+	pub rax_2: f64,
+	pub rax_3: f64,
+	pub rax_4: f64,
+	pub rax_5: f64,
+	pub rax_6: f64,
+}
+
+/// The positions of the external axes.
+///
+/// Unused axes have the value [`ExtJoint::UNUSED`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExtJoint {
+	pub eax_a: f64,
+	pub eax_b: f64,
+	pub eax_c: f64,
+	pub eax_d: f64,
+	pub eax_e: f64,
+	pub eax_f: f64,
+}
+
+impl ExtJoint {
+	/// The sentinel value for unused axes: 9E9.
+	pub const UNUSED: f64 = 9E9;
+
+	/// External axis positions with all axes unused.
+	pub fn unused() -> Self {
+		Self {
+			eax_a: Self::UNUSED,
+			eax_b: Self::UNUSED,
+			eax_c: Self::UNUSED,
+			eax_d: Self::UNUSED,
+			eax_e: Self::UNUSED,
+			eax_f: Self::UNUSED,
+		}
+	}
+}
+
+/// A coordinate transformation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pose {
+	pub trans: Pos,
+	pub rot: Orient,
+}
+
+/// A Cartesian robot target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RobTarget {
+	pub trans: Pos,
+	pub rot: Orient,
+	pub robconf: ConfData,
+	pub extax: ExtJoint,
+}
+
+/// A joint space robot target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointTarget {
+	pub robax: RobJoint,
+	pub extax: ExtJoint,
+}
+
+/// The value of a RAPID symbol.
+///
+/// Numbers and strings parsed from a literal remember their original [`Spelling`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RapidValue {
+	Num(f64, Spelling),
+	Bool(bool),
+	String(String, Spelling),
+	RobTarget(RobTarget),
+	JointTarget(JointTarget),
+	Pose(Pose),
+
+	/// A record of a data type without a specific model.
+	Record(Vec<RapidValue>),
+
+	/// An array of values.
+	Array(Vec<RapidValue>),
+}
+
+impl RapidValue {
+	/// Create a num value without an original spelling.
+	pub fn num(value: f64) -> Self {
+		Self::Num(value, Spelling::default())
+	}
+
+	/// Create a string value without an original spelling.
+	pub fn string(value: impl Into<String>) -> Self {
+		Self::String(value.into(), Spelling::default())
+	}
+}
+
+/// The original spelling of a parsed literal.
+///
+/// Formatting a parsed number or string emits the literal exactly as it was written,
+/// like `9E+09` or `"\41"`, as long as it still matches the value.
+/// Values without a spelling are formatted in the shortest form.
+///
+/// The spelling does not affect equality: `9E9` and `9E+09` compare equal.
+#[derive(Clone, Debug, Default)]
+pub struct Spelling(Option<String>);
+
+impl Spelling {
+	pub(super) fn new(raw: &str) -> Self {
+		Self(Some(raw.into()))
+	}
+
+	/// Get the literal as it was written, if the value was parsed.
+	pub fn as_str(&self) -> Option<&str> {
+		self.0.as_deref()
+	}
+}
+
+impl PartialEq for Spelling {
+	fn eq(&self, _other: &Self) -> bool {
+		true
+	}
+}
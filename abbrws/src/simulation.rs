@@ -0,0 +1,82 @@
+use crate::Client;
+use crate::Error;
+use crate::SignalValue;
+
+/// Guard that keeps a signal simulated.
+///
+/// The original simulation state and value of the signal are restored when the guard is dropped,
+/// or when [`SimulationGuard::restore`] is called.
+///
+/// Restoring the signal on drop is done by a background task on the current Tokio runtime,
+/// so any error is silently ignored.
+/// If the guard is dropped outside of a Tokio runtime, the signal stays simulated.
+/// Use [`SimulationGuard::restore`] to restore the signal explicitly and to see any errors.
+pub struct SimulationGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	client: Option<Client<C>>,
+	signal: String,
+	original: Option<SignalValue>,
+}
+
+impl<C> SimulationGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	/// Create a new guard.
+	///
+	/// If `original` is `None`, the signal was not simulated before and will be unsimulated when restored.
+	/// Otherwise, the signal will be simulated with the original value.
+	pub(crate) fn new(client: Client<C>, signal: String, original: Option<SignalValue>) -> Self {
+		Self {
+			client: Some(client),
+			signal,
+			original,
+		}
+	}
+
+	/// The signal that is being simulated.
+	pub fn signal(&self) -> &str {
+		&self.signal
+	}
+
+	/// Restore the original simulation state and value of the signal.
+	pub async fn restore(mut self) -> Result<(), Error> {
+		match self.client.take() {
+			Some(mut client) => restore(&mut client, &self.signal, self.original).await,
+			None => Ok(()),
+		}
+	}
+}
+
+impl<C> Drop for SimulationGuard<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	fn drop(&mut self) {
+		let mut client = match self.client.take() {
+			Some(x) => x,
+			None => return,
+		};
+
+		if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+			let signal = std::mem::take(&mut self.signal);
+			let original = self.original;
+			runtime.spawn(async move {
+				// Nobody to report the error to, so ignore it.
+				let _ = restore(&mut client, &signal, original).await;
+			});
+		}
+	}
+}
+
+async fn restore<C>(client: &mut Client<C>, signal: &str, original: Option<SignalValue>) -> Result<(), Error>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	match original {
+		Some(value) => client.set_simulated_value(signal, value).await,
+		None => client.unsimulate_signal(signal).await,
+	}
+}
@@ -0,0 +1,252 @@
+use futures_util::Stream;
+use futures_util::StreamExt;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+use crate::Client;
+use crate::Error;
+use crate::MissingHeaderError;
+use crate::Signal;
+use crate::parse;
+use crate::url_encode_query_value;
+
+/// The WebSocket sub-protocol used by RWS for subscription events.
+const SUBSCRIPTION_PROTOCOL: &str = "robapi2_subscription";
+
+/// Subscriptions that are closed quicker than this without delivering any event count as failed.
+const MIN_SUBSCRIPTION_LIFETIME: Duration = Duration::from_secs(1);
+
+/// The maximum number of failed subscriptions in a row before giving up.
+const MAX_FAILED_SUBSCRIPTIONS: usize = 3;
+
+type WebSocket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+type NextFuture<C> = Pin<Box<dyn Future<Output = (SubscriptionState<C>, Option<Result<Signal, Error>>)> + Send>>;
+
+/// A subscription to signal changes.
+///
+/// The subscription is a [`Stream`] that yields the updated [`Signal`] every time the value of a subscribed signal changes.
+///
+/// When the WebSocket connection is closed by the controller (for example because the session expired),
+/// the subscription is automatically renewed.
+///
+/// Dropping the subscription closes the WebSocket connection,
+/// but the subscription remains registered on the controller until the session expires.
+/// Use [`SignalSubscription::close`] to remove it from the controller immediately.
+pub struct SignalSubscription<C> {
+	state: Option<SubscriptionState<C>>,
+	pending: Option<NextFuture<C>>,
+}
+
+struct SubscriptionState<C> {
+	client: Client<C>,
+	signals: BTreeMap<String, Signal>,
+	resource: Option<http::Uri>,
+	socket: Option<WebSocket>,
+	connected_at: Instant,
+	received_event: bool,
+	failed_subscriptions: usize,
+	queue: VecDeque<Signal>,
+	done: bool,
+}
+
+impl<C> SignalSubscription<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	/// Create a subscription for the given signals.
+	pub(crate) async fn new(client: Client<C>, signals: Vec<Signal>) -> Result<Self, Error> {
+		let mut state = SubscriptionState {
+			client,
+			signals: signals.into_iter().map(|x| (x.title.clone(), x)).collect(),
+			resource: None,
+			socket: None,
+			connected_at: Instant::now(),
+			received_event: false,
+			failed_subscriptions: 0,
+			queue: VecDeque::new(),
+			done: false,
+		};
+		state.subscribe().await?;
+		Ok(Self {
+			state: Some(state),
+			pending: None,
+		})
+	}
+
+	/// Close the WebSocket connection and remove the subscription from the controller.
+	pub async fn close(mut self) -> Result<(), Error> {
+		match self.state.take() {
+			Some(state) => state.close().await,
+			None => Ok(()),
+		}
+	}
+}
+
+impl<C> SubscriptionState<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	/// Register a new subscription on the controller and connect to the WebSocket.
+	async fn subscribe(&mut self) -> Result<(), Error> {
+		self.socket = None;
+
+		let url : http::Uri = format!("{}/subscription?json=1", self.client.root_url).parse().unwrap();
+		let mut data = Vec::new();
+		for (i, title) in self.signals.keys().enumerate() {
+			let resource = format!("/rw/iosystem/signals/{};state", title);
+			data.push(format!("resources={i}&{i}={}&{i}-p=1", url_encode_query_value(&resource), i = i + 1));
+		}
+		let data = data.join("&");
+
+		let response = self.client.request_raw(move || hyper::Request::post(url.clone())
+			.header(hyper::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+			.body(data.clone().into())
+		).await?;
+
+		// The Location header points to the WebSocket for the subscription.
+		let location = response.headers().get(hyper::header::LOCATION)
+			.ok_or(MissingHeaderError { name: "Location" })?;
+		let location : http::Uri = location.to_str()?.parse()?;
+		let authority = location.authority()
+			.or_else(|| self.client.root_url.authority())
+			.map(|x| x.to_string())
+			.unwrap_or_default();
+		let id = location.path().rsplit('/').next().unwrap_or_default();
+		self.resource = Some(format!("{}/subscription/{}", self.client.root_url, id).parse()?);
+
+		let mut request = format!("ws://{}{}", authority, location.path()).into_client_request()?;
+		let headers = request.headers_mut();
+		headers.insert(hyper::header::SEC_WEBSOCKET_PROTOCOL, hyper::header::HeaderValue::from_static(SUBSCRIPTION_PROTOCOL));
+		for cookie in self.client.cookie_headers() {
+			headers.append(hyper::header::COOKIE, cookie);
+		}
+
+		let (socket, _response) = tokio_tungstenite::connect_async(request).await?;
+		self.socket = Some(socket);
+		self.connected_at = Instant::now();
+		self.received_event = false;
+		Ok(())
+	}
+
+	/// Get the next item for the stream.
+	async fn next(mut self) -> (Self, Option<Result<Signal, Error>>) {
+		let item = self.next_item().await;
+		(self, item)
+	}
+
+	async fn next_item(&mut self) -> Option<Result<Signal, Error>> {
+		loop {
+			if let Some(signal) = self.queue.pop_front() {
+				return Some(Ok(signal));
+			}
+
+			if self.done {
+				return None;
+			}
+
+			let socket = match &mut self.socket {
+				Some(x) => x,
+				None => {
+					if self.failed_subscriptions >= MAX_FAILED_SUBSCRIPTIONS {
+						self.done = true;
+						return Some(Err(tungstenite::Error::ConnectionClosed.into()));
+					}
+					if let Err(e) = self.subscribe().await {
+						self.done = true;
+						return Some(Err(e));
+					}
+					continue;
+				},
+			};
+
+			match socket.next().await {
+				Some(Ok(tungstenite::Message::Text(message))) => {
+					self.received_event = true;
+					self.failed_subscriptions = 0;
+					if let Err(e) = self.process_events(&message) {
+						return Some(Err(e));
+					}
+				},
+				Some(Ok(tungstenite::Message::Close(_))) | None => self.disconnected(),
+				Some(Ok(_)) => (),
+				Some(Err(e)) => {
+					self.disconnected();
+					return Some(Err(e.into()));
+				},
+			}
+		}
+	}
+
+	/// Forget the current WebSocket, so that a new subscription is made on the next poll.
+	fn disconnected(&mut self) {
+		self.socket = None;
+		if !self.received_event && self.connected_at.elapsed() < MIN_SUBSCRIPTION_LIFETIME {
+			self.failed_subscriptions += 1;
+		}
+	}
+
+	/// Parse an event message and queue the updated signals.
+	fn process_events(&mut self, message: &str) -> Result<(), Error> {
+		for event in parse::subscription::parse_signal_events(message) {
+			if let Some(signal) = self.signals.get_mut(&event.title) {
+				signal.lvalue = parse::signal::parse_value_str(signal.kind, &event.lvalue)?;
+				self.queue.push_back(signal.clone());
+			}
+		}
+		Ok(())
+	}
+
+	async fn close(mut self) -> Result<(), Error> {
+		if let Some(mut socket) = self.socket.take() {
+			// The subscription is removed below anyway, so ignore errors here.
+			let _ = socket.close(None).await;
+		}
+
+		if let Some(url) = self.resource.take() {
+			self.client.request_raw(|| hyper::Request::delete(url.clone()).body(hyper::Body::empty())).await?;
+		}
+
+		Ok(())
+	}
+}
+
+// We never pin any of the fields, only the boxed future.
+impl<C> Unpin for SignalSubscription<C> {}
+
+impl<C> Stream for SignalSubscription<C>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	type Item = Result<Signal, Error>;
+
+	fn poll_next(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		if this.pending.is_none() {
+			let state = match this.state.take() {
+				Some(x) => x,
+				None => return Poll::Ready(None),
+			};
+			this.pending = Some(Box::pin(state.next()));
+		}
+
+		let (state, item) = match this.pending.as_mut().unwrap().as_mut().poll(context) {
+			Poll::Pending => return Poll::Pending,
+			Poll::Ready(x) => x,
+		};
+
+		this.pending = None;
+		if item.is_some() {
+			this.state = Some(state);
+		}
+		Poll::Ready(item)
+	}
+}
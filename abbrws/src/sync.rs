@@ -0,0 +1,561 @@
+//! One-way synchronization of a directory tree between the host and the controller.
+//!
+//! Synchronizing is split in two steps: [`Client::plan_sync`] compares the two trees and returns a [`SyncPlan`],
+//! and [`Client::apply_sync`] carries out the plan.
+//! This allows the plan to be shown to a user before anything is changed.
+//!
+//! The controller reports modification times in its local time, without time zone.
+//! Unless [`SyncOptions::utc_offset_s`] is given, files are therefore compared by size only.
+
+use crate::Client;
+use crate::DirEntry;
+use crate::Error;
+use crate::FileServiceErrorKind;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+/// Modification times closer together than this are considered equal.
+///
+/// This covers file systems that store modification times with a resolution of two seconds.
+const TIME_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// The direction to synchronize in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SyncDirection {
+	/// Make the remote tree match the local tree.
+	Upload,
+
+	/// Make the local tree match the remote tree.
+	Download,
+}
+
+/// Options for [`Client::plan_sync`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncOptions {
+	/// Delete entries that only exist at the destination.
+	pub delete: bool,
+
+	/// The offset of the controller clock from UTC in seconds, like `3600` for UTC+1.
+	///
+	/// Modification times are only compared if the offset is known.
+	pub utc_offset_s: Option<i64>,
+}
+
+/// Why a file is transferred.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SyncReason {
+	/// The file does not exist at the destination.
+	Missing,
+
+	/// The file has a different size at the destination.
+	SizeDiffers,
+
+	/// The source file was modified after the destination file.
+	///
+	/// Only used if the UTC offset of the controller is given in the [`SyncOptions`].
+	Newer,
+}
+
+/// Why an entry is left alone.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SkipReason {
+	/// The destination is read-only.
+	ReadOnly,
+
+	/// The entry is a file on one side and a directory on the other.
+	TypeConflict,
+}
+
+/// A single step of a [`SyncPlan`].
+///
+/// Paths are relative to the synchronized directories and use `/` as separator.
+/// The empty path refers to the destination directory itself.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SyncAction {
+	/// Create a directory at the destination.
+	CreateDirectory(String),
+
+	/// Copy a file from the source to the destination.
+	Transfer { path: String, reason: SyncReason },
+
+	/// Delete an entry that only exists at the destination.
+	Delete { path: String, directory: bool },
+
+	/// Leave an entry that differs alone.
+	Skip { path: String, reason: SkipReason },
+}
+
+/// The steps needed to synchronize a directory tree, in the order they must be performed.
+#[derive(Clone, Debug)]
+pub struct SyncPlan {
+	pub direction: SyncDirection,
+	pub local: PathBuf,
+	pub remote: String,
+	pub actions: Vec<SyncAction>,
+}
+
+/// An entry in a directory tree, on either side.
+#[derive(Clone, Debug)]
+enum Entry {
+	Directory,
+	File {
+		size: u64,
+		modified: Option<SystemTime>,
+		read_only: bool,
+	},
+}
+
+impl SyncReason {
+	/// A short description of the reason.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Missing     => "missing",
+			Self::SizeDiffers => "size differs",
+			Self::Newer       => "newer",
+		}
+	}
+}
+
+impl SkipReason {
+	/// A short description of the reason.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::ReadOnly     => "read-only",
+			Self::TypeConflict => "file and directory conflict",
+		}
+	}
+}
+
+impl std::fmt::Display for SyncReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl std::fmt::Display for SkipReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+impl SyncPlan {
+	/// Check if the trees are already in sync.
+	///
+	/// Skipped entries are not counted, since applying the plan would not change them.
+	pub fn is_empty(&self) -> bool {
+		self.actions.iter().all(|x| matches!(x, SyncAction::Skip { .. }))
+	}
+
+	/// The full path of an entry on the controller.
+	pub fn remote_path(&self, path: &str) -> String {
+		join_remote(&self.remote, path)
+	}
+
+	/// The full path of an entry on the host.
+	pub fn local_path(&self, path: &str) -> PathBuf {
+		join_local(&self.local, path)
+	}
+
+	/// The full path of an entry at the destination, for display.
+	fn destination(&self, path: &str) -> String {
+		match self.direction {
+			SyncDirection::Upload => self.remote_path(path),
+			SyncDirection::Download => self.local_path(path).display().to_string(),
+		}
+	}
+}
+
+/// Shows one line per action, with the full destination path.
+impl std::fmt::Display for SyncPlan {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let transfer = match self.direction {
+			SyncDirection::Upload => "upload",
+			SyncDirection::Download => "download",
+		};
+		for action in &self.actions {
+			match action {
+				SyncAction::CreateDirectory(path) => writeln!(f, "mkdir    {}", self.destination(path))?,
+				SyncAction::Transfer { path, reason } => writeln!(f, "{:<8} {} ({})", transfer, self.destination(path), reason)?,
+				SyncAction::Delete { path, directory: false } => writeln!(f, "delete   {}", self.destination(path))?,
+				SyncAction::Delete { path, directory: true } => writeln!(f, "delete   {}/", self.destination(path))?,
+				SyncAction::Skip { path, reason } => writeln!(f, "skip     {} ({})", self.destination(path), reason)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Compare a local and a remote directory tree.
+pub(crate) async fn plan<C>(client: &mut Client<C>, local: &Path, remote: &str, direction: SyncDirection, options: &SyncOptions) -> Result<SyncPlan, Error>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	let local_tree = list_local(local).await?;
+	let remote_tree = list_remote(client, remote, options.utc_offset_s).await?;
+	let delete = options.delete;
+
+	// A missing source is an error: treating it as empty would delete everything at the destination.
+	let actions = match direction {
+		SyncDirection::Upload => {
+			let local_tree = local_tree.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, format!("{}: no such directory", local.display())))?;
+			compare(&local_tree, remote_tree.as_ref(), delete)
+		},
+		SyncDirection::Download => {
+			let remote_tree = remote_tree.ok_or_else(|| remote_not_found(remote))?;
+			compare(&remote_tree, local_tree.as_ref(), delete)
+		},
+	};
+
+	Ok(SyncPlan {
+		direction,
+		local: local.to_path_buf(),
+		remote: remote.trim_end_matches('/').to_string(),
+		actions,
+	})
+}
+
+/// Carry out a plan, calling `progress` before each action.
+pub(crate) async fn apply<C>(client: &mut Client<C>, plan: &SyncPlan, mut progress: impl FnMut(&SyncAction)) -> Result<(), Error>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	for action in &plan.actions {
+		progress(action);
+		match (plan.direction, action) {
+			(_, SyncAction::Skip { .. }) => (),
+			(SyncDirection::Upload, SyncAction::CreateDirectory(path)) => {
+				client.create_directory(&plan.remote_path(path)).await?;
+			},
+			(SyncDirection::Upload, SyncAction::Transfer { path, .. }) => {
+				client.upload_file_from_path(plan.local_path(path), &plan.remote_path(path), mime::APPLICATION_OCTET_STREAM, |_| ()).await?;
+			},
+			(SyncDirection::Upload, SyncAction::Delete { path, directory: false }) => {
+				client.delete_file(&plan.remote_path(path)).await?;
+			},
+			(SyncDirection::Upload, SyncAction::Delete { path, directory: true }) => {
+				client.delete_directory(&plan.remote_path(path), true).await?;
+			},
+			(SyncDirection::Download, SyncAction::CreateDirectory(path)) => {
+				tokio::fs::create_dir_all(plan.local_path(path)).await?;
+			},
+			(SyncDirection::Download, SyncAction::Transfer { path, .. }) => {
+				client.download_file_to_path(&plan.remote_path(path), plan.local_path(path), |_| ()).await?;
+			},
+			(SyncDirection::Download, SyncAction::Delete { path, directory: false }) => {
+				tokio::fs::remove_file(plan.local_path(path)).await?;
+			},
+			(SyncDirection::Download, SyncAction::Delete { path, directory: true }) => {
+				tokio::fs::remove_dir_all(plan.local_path(path)).await?;
+			},
+		}
+	}
+	Ok(())
+}
+
+/// Determine the actions needed to make `destination` match `source`.
+///
+/// A destination of `None` does not exist yet.
+fn compare(source: &BTreeMap<String, Entry>, destination: Option<&BTreeMap<String, Entry>>, delete: bool) -> Vec<SyncAction> {
+	let empty = BTreeMap::new();
+	let mut actions = Vec::new();
+	if destination.is_none() {
+		actions.push(SyncAction::CreateDirectory(String::new()));
+	}
+	let destination = destination.unwrap_or(&empty);
+
+	// Paths below a directory that can not be synchronized are skipped along with it.
+	let mut skipped_directories : Vec<&str> = Vec::new();
+	let is_below = |path: &str, directories: &[&str]| directories.iter().any(|dir| path.starts_with(dir) && path[dir.len()..].starts_with('/'));
+
+	// The trees are sorted by path, so directories come before their contents.
+	for (path, entry) in source {
+		if is_below(path, &skipped_directories) {
+			continue;
+		}
+		let existing = destination.get(path);
+		match (entry, existing) {
+			(Entry::Directory, None) => actions.push(SyncAction::CreateDirectory(path.clone())),
+			(Entry::Directory, Some(Entry::Directory)) => (),
+			(Entry::Directory, Some(Entry::File { .. })) => {
+				skipped_directories.push(path);
+				actions.push(SyncAction::Skip { path: path.clone(), reason: SkipReason::TypeConflict });
+			},
+			(Entry::File { .. }, None) => actions.push(SyncAction::Transfer { path: path.clone(), reason: SyncReason::Missing }),
+			(Entry::File { .. }, Some(Entry::Directory)) => {
+				skipped_directories.push(path);
+				actions.push(SyncAction::Skip { path: path.clone(), reason: SkipReason::TypeConflict });
+			},
+			(Entry::File { size, modified, .. }, Some(Entry::File { size: dest_size, modified: dest_modified, read_only })) => {
+				let reason = if size != dest_size {
+					SyncReason::SizeDiffers
+				} else if is_newer(*modified, *dest_modified) {
+					SyncReason::Newer
+				} else {
+					continue;
+				};
+				if *read_only {
+					actions.push(SyncAction::Skip { path: path.clone(), reason: SkipReason::ReadOnly });
+				} else {
+					actions.push(SyncAction::Transfer { path: path.clone(), reason });
+				}
+			},
+		}
+	}
+
+	if delete {
+		// Directories are deleted as a whole, unless they contain read-only files.
+		// Those directories are kept, and their other contents are deleted one by one.
+		let mut deleted_directories : Vec<&str> = Vec::new();
+		for (path, entry) in destination {
+			if source.contains_key(path) || is_below(path, &deleted_directories) || is_below(path, &skipped_directories) {
+				continue;
+			}
+			match entry {
+				Entry::Directory if has_read_only_below(destination, path) => (),
+				Entry::Directory => {
+					deleted_directories.push(path);
+					actions.push(SyncAction::Delete { path: path.clone(), directory: true });
+				},
+				Entry::File { read_only: true, .. } => actions.push(SyncAction::Skip { path: path.clone(), reason: SkipReason::ReadOnly }),
+				Entry::File { read_only: false, .. } => actions.push(SyncAction::Delete { path: path.clone(), directory: false }),
+			}
+		}
+	}
+
+	actions
+}
+
+/// Check if a directory tree contains read-only files below the given directory.
+fn has_read_only_below(tree: &BTreeMap<String, Entry>, directory: &str) -> bool {
+	let prefix = format!("{}/", directory);
+	tree.range(prefix.clone()..)
+		.take_while(|(path, _)| path.starts_with(&prefix))
+		.any(|(_, entry)| matches!(entry, Entry::File { read_only: true, .. }))
+}
+
+/// Check if a source file was modified after the destination file.
+///
+/// If either time is unknown, the files are assumed to be the same.
+fn is_newer(source: Option<SystemTime>, destination: Option<SystemTime>) -> bool {
+	match (source, destination) {
+		(Some(source), Some(destination)) => source > destination + TIME_TOLERANCE,
+		_ => false,
+	}
+}
+
+/// List a local directory tree recursively, or return `None` if it doesn't exist.
+async fn list_local(root: &Path) -> Result<Option<BTreeMap<String, Entry>>, Error> {
+	match tokio::fs::metadata(root).await {
+		Ok(_) => (),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e.into()),
+	}
+
+	let mut tree = BTreeMap::new();
+	let mut pending = vec![String::new()];
+	while let Some(directory) = pending.pop() {
+		let mut entries = tokio::fs::read_dir(join_local(root, &directory)).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let name = entry.file_name().to_string_lossy().into_owned();
+			let path = join_relative(&directory, &name);
+			let metadata = entry.metadata().await?;
+			if metadata.is_dir() {
+				pending.push(path.clone());
+				tree.insert(path, Entry::Directory);
+			} else if metadata.is_file() {
+				tree.insert(path, Entry::File {
+					size: metadata.len(),
+					modified: metadata.modified().ok(),
+					read_only: metadata.permissions().readonly(),
+				});
+			}
+		}
+	}
+	Ok(Some(tree))
+}
+
+/// List a remote directory tree recursively, or return `None` if it doesn't exist.
+///
+/// Modification times are only known if the UTC offset of the controller is given.
+async fn list_remote<C>(client: &mut Client<C>, root: &str, utc_offset_s: Option<i64>) -> Result<Option<BTreeMap<String, Entry>>, Error>
+where
+	C: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+{
+	let mut tree = BTreeMap::new();
+	let mut pending = vec![String::new()];
+	while let Some(directory) = pending.pop() {
+		let directory_path = join_remote(root, &directory);
+		let entries = match client.list_files(&directory_path).await.map_err(|e| crate::file_service_error(&directory_path, e)) {
+			Ok(x) => x,
+			Err(Error::FileService(e)) if directory.is_empty() && e.kind == FileServiceErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e),
+		};
+		for entry in entries {
+			let path = join_relative(&directory, entry.name());
+			match entry {
+				DirEntry::Directory(_) => {
+					pending.push(path.clone());
+					tree.insert(path, Entry::Directory);
+				},
+				DirEntry::File(file) => {
+					tree.insert(path, Entry::File {
+						size: file.size as u64,
+						modified: utc_offset_s.and_then(|offset| to_utc(file.modified()?, offset)),
+						read_only: file.read_only,
+					});
+				},
+				DirEntry::Device(_) => (),
+			}
+		}
+	}
+	Ok(Some(tree))
+}
+
+/// Convert a controller time that was parsed as UTC to the real UTC time.
+fn to_utc(time: SystemTime, utc_offset_s: i64) -> Option<SystemTime> {
+	let offset = Duration::from_secs(utc_offset_s.unsigned_abs());
+	if utc_offset_s >= 0 {
+		time.checked_sub(offset)
+	} else {
+		time.checked_add(offset)
+	}
+}
+
+/// Create the error for a missing remote directory.
+fn remote_not_found(path: &str) -> Error {
+	crate::FileServiceError {
+		kind: FileServiceErrorKind::NotFound,
+		path: path.to_string(),
+		error: crate::RemoteFailureError {
+			http_status: hyper::StatusCode::NOT_FOUND,
+			code: None,
+			message: String::new(),
+		},
+	}.into()
+}
+
+fn join_relative(directory: &str, name: &str) -> String {
+	if directory.is_empty() {
+		name.to_string()
+	} else {
+		format!("{}/{}", directory, name)
+	}
+}
+
+fn join_remote(root: &str, path: &str) -> String {
+	let root = root.trim_end_matches('/');
+	if path.is_empty() {
+		root.to_string()
+	} else {
+		format!("{}/{}", root, path)
+	}
+}
+
+fn join_local(root: &Path, path: &str) -> PathBuf {
+	path.split('/').filter(|x| !x.is_empty()).fold(root.to_path_buf(), |result, component| result.join(component))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	fn file(size: u64, modified: u64, read_only: bool) -> Entry {
+		Entry::File {
+			size,
+			modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(modified)),
+			read_only,
+		}
+	}
+
+	fn tree(entries: Vec<(&str, Entry)>) -> BTreeMap<String, Entry> {
+		entries.into_iter().map(|(path, entry)| (path.to_string(), entry)).collect()
+	}
+
+	#[test]
+	fn test_compare() {
+		let source = tree(vec![
+			("config", Entry::Directory),
+			("config/sys.cfg", file(100, 1000, false)),
+			("main.mod", file(200, 1000, false)),
+			("same.mod", file(300, 1000, false)),
+			("newer.mod", file(300, 2000, false)),
+			("locked.mod", file(400, 1000, false)),
+		]);
+		let destination = tree(vec![
+			("main.mod", file(250, 1000, false)),
+			("same.mod", file(300, 1001, false)),
+			("newer.mod", file(300, 1000, false)),
+			("locked.mod", file(500, 1000, true)),
+			("old", Entry::Directory),
+			("old/x.mod", file(1, 1000, false)),
+			("old.mod", file(1, 1000, true)),
+		]);
+
+		assert!(compare(&source, Some(&destination), false) == [
+			SyncAction::CreateDirectory("config".into()),
+			SyncAction::Transfer { path: "config/sys.cfg".into(), reason: SyncReason::Missing },
+			SyncAction::Skip { path: "locked.mod".into(), reason: SkipReason::ReadOnly },
+			SyncAction::Transfer { path: "main.mod".into(), reason: SyncReason::SizeDiffers },
+			SyncAction::Transfer { path: "newer.mod".into(), reason: SyncReason::Newer },
+		]);
+
+		let actions = compare(&source, Some(&destination), true);
+		assert!(actions[5..] == [
+			SyncAction::Delete { path: "old".into(), directory: true },
+			SyncAction::Skip { path: "old.mod".into(), reason: SkipReason::ReadOnly },
+		]);
+	}
+
+	#[test]
+	fn test_compare_delete_read_only_below() {
+		let source = tree(vec![]);
+		let destination = tree(vec![
+			("old", Entry::Directory),
+			("old.mod", file(1, 1000, false)),
+			("old/locked.cfg", file(1, 1000, true)),
+			("old/stale", Entry::Directory),
+			("old/stale/x.mod", file(1, 1000, false)),
+			("old/x.mod", file(1, 1000, false)),
+			("other", Entry::Directory),
+			("other/y.mod", file(1, 1000, false)),
+		]);
+
+		// The directory with the read-only file is kept, but everything else in it is deleted.
+		assert!(compare(&source, Some(&destination), true) == [
+			SyncAction::Delete { path: "old.mod".into(), directory: false },
+			SyncAction::Skip { path: "old/locked.cfg".into(), reason: SkipReason::ReadOnly },
+			SyncAction::Delete { path: "old/stale".into(), directory: true },
+			SyncAction::Delete { path: "old/x.mod".into(), directory: false },
+			SyncAction::Delete { path: "other".into(), directory: true },
+		]);
+	}
+
+	#[test]
+	fn test_to_utc() {
+		let time = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000);
+		assert!(to_utc(time, 3600) == Some(SystemTime::UNIX_EPOCH + Duration::from_secs(6_400)));
+		assert!(to_utc(time, -3600) == Some(SystemTime::UNIX_EPOCH + Duration::from_secs(13_600)));
+		assert!(to_utc(time, 0) == Some(time));
+	}
+
+	#[test]
+	fn test_compare_missing_destination() {
+		let source = tree(vec![
+			("a.mod", file(1, 1000, false)),
+		]);
+		assert!(compare(&source, None, true) == [
+			SyncAction::CreateDirectory("".into()),
+			SyncAction::Transfer { path: "a.mod".into(), reason: SyncReason::Missing },
+		]);
+	}
+
+	#[test]
+	fn test_join_local() {
+		assert!(join_local(Path::new("/tmp/robot"), "") == Path::new("/tmp/robot"));
+		assert!(join_local(Path::new("/tmp/robot"), "config/sys.cfg") == Path::new("/tmp/robot/config/sys.cfg"));
+	}
+}
@@ -0,0 +1,106 @@
+//! Streaming file transfers.
+
+use crate::Error;
+use crate::Mime;
+use futures_util::Stream;
+use hyper::body::Bytes;
+use hyper::body::HttpBody;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+/// Progress of a file transfer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Progress {
+	/// The number of bytes transferred so far.
+	pub transferred: u64,
+
+	/// The total size of the transfer, if known.
+	pub total: Option<u64>,
+}
+
+impl Progress {
+	/// The fraction of the transfer that is done, between 0 and 1, if the total size is known.
+	pub fn fraction(&self) -> Option<f64> {
+		match self.total {
+			Some(0) => Some(1.0),
+			Some(total) => Some(self.transferred as f64 / total as f64),
+			None => None,
+		}
+	}
+}
+
+/// A file being downloaded from the controller.
+///
+/// The download is a [`Stream`] of chunks of the file contents.
+#[derive(Debug)]
+pub struct FileDownload {
+	content_type: Mime,
+	content_length: Option<u64>,
+	body: hyper::Body,
+}
+
+impl FileDownload {
+	pub(crate) fn new(response: hyper::Response<hyper::Body>) -> Result<Self, Error> {
+		let content_type = crate::get_content_type(&response)?;
+		let content_length = response.headers().get(hyper::header::CONTENT_LENGTH)
+			.and_then(|x| x.to_str().ok())
+			.and_then(|x| x.parse().ok());
+		Ok(Self {
+			content_type,
+			content_length,
+			body: response.into_body(),
+		})
+	}
+
+	/// The content type of the file.
+	pub fn content_type(&self) -> &Mime {
+		&self.content_type
+	}
+
+	/// The size of the file in bytes, from the `Content-Length` header.
+	pub fn content_length(&self) -> Option<u64> {
+		self.content_length
+	}
+}
+
+impl Stream for FileDownload {
+	type Item = Result<Bytes, Error>;
+
+	fn poll_next(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+		Pin::new(&mut self.body).poll_data(context).map_err(Error::from)
+	}
+}
+
+/// Size of the chunks read from a reader when uploading.
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Read all data from `reader` and send it over a request body channel.
+///
+/// If reading fails, the body is aborted so the request does not complete with partial data.
+pub(crate) async fn send_body<R>(mut reader: R, mut sender: hyper::body::Sender, total: Option<u64>, mut progress: impl FnMut(Progress)) -> Result<(), Error>
+where
+	R: tokio::io::AsyncRead + Unpin,
+{
+	use tokio::io::AsyncReadExt;
+
+	let mut buffer = vec![0; UPLOAD_CHUNK_SIZE];
+	let mut progress_state = Progress { transferred: 0, total };
+	loop {
+		let len = match reader.read(&mut buffer).await {
+			Ok(0) => return Ok(()),
+			Ok(len) => len,
+			Err(e) => {
+				sender.abort();
+				return Err(e.into());
+			},
+		};
+
+		// If the receiver is gone, the request already ended and will report why.
+		if sender.send_data(Bytes::copy_from_slice(&buffer[..len])).await.is_err() {
+			return Ok(());
+		}
+		progress_state.transferred += len as u64;
+		progress(progress_state);
+	}
+}
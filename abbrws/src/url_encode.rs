@@ -8,6 +8,8 @@ fn must_escape(byte: u8) -> bool {
 		b'#' => true,
 		b'%' => true,
 		b'&' => true,
+		b'+' => true,
+		b' ' => true,
 		b'=' => true,
 		_ => byte > 127,
 	}
@@ -32,7 +34,7 @@ impl std::fmt::Display for UrlEncodeQueryValue<'_> {
 	}
 }
 
-pub fn url_encode_query_value<T: AsRef<[u8]> + ?Sized>(data: &T) -> UrlEncodeQueryValue {
+pub fn url_encode_query_value<T: AsRef<[u8]> + ?Sized>(data: &T) -> UrlEncodeQueryValue<'_> {
 	UrlEncodeQueryValue { data: data.as_ref() }
 }
 
@@ -48,6 +50,8 @@ mod test {
 		assert!("%25" == format!("{}", url_encode_query_value("%")));
 		assert!("%26" == format!("{}", url_encode_query_value("&")));
 		assert!("%3D" == format!("{}", url_encode_query_value("=")));
+		assert!("%2B" == format!("{}", url_encode_query_value("+")));
+		assert!("%20" == format!("{}", url_encode_query_value(" ")));
 		assert!("%23%25%26%3D" == format!("{}", url_encode_query_value("#%&=")));
 		assert!("aap" == format!("{}", url_encode_query_value("aap")));
 		assert!("aap%3Dnoot" == format!("{}", url_encode_query_value("aap=noot")));
@@ -0,0 +1,81 @@
+use assert2::assert;
+use futures_util::TryStreamExt;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match request.path.as_str() {
+		"/fileservice/$HOME/backup.tar/" => hyper::Response::builder()
+			.header(hyper::header::CONTENT_TYPE, "application/octet-stream")
+			.header(hyper::header::CONTENT_LENGTH, 1_000_000)
+			.body(mock::contents().into())
+			.unwrap(),
+		_ => mock::error(StatusCode::NOT_FOUND, -1073442813, "File not found"),
+	}
+}
+
+#[tokio::test]
+async fn test_download_file_to() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let mut data = Vec::new();
+	let mut progress = Vec::new();
+	let content_type = client.download_file_to("$HOME/backup.tar", &mut data, |x| progress.push(x)).await.unwrap();
+	assert!(content_type == mime::APPLICATION_OCTET_STREAM);
+	assert!(data == mock::contents());
+
+	let last = progress.last().copied().unwrap();
+	assert!(last == abbrws::Progress { transferred: 1_000_000, total: Some(1_000_000) });
+	assert!(last.fraction() == Some(1.0));
+	assert!(progress.windows(2).all(|x| x[0].transferred < x[1].transferred));
+}
+
+#[tokio::test]
+async fn test_download_file_stream() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let download = client.download_file_stream("$HOME/backup.tar").await.unwrap();
+	assert!(download.content_length() == Some(1_000_000));
+	let chunks : Vec<_> = download.try_collect().await.unwrap();
+	assert!(chunks.concat() == mock::contents());
+}
+
+#[tokio::test]
+async fn test_download_missing_file() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let result = client.download_file_stream("$HOME/missing.tar").await;
+	assert!(let Err(abbrws::Error::FileService(abbrws::FileServiceError { kind: abbrws::FileServiceErrorKind::NotFound, .. })) = result);
+}
+
+#[tokio::test]
+async fn test_download_file_to_path() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let directory = std::env::temp_dir().join(format!("abbrws-download-test-{}", std::process::id()));
+	let _ = std::fs::remove_dir_all(&directory);
+	std::fs::create_dir_all(&directory).unwrap();
+	let local_path = directory.join("backup.tar");
+	std::fs::write(&local_path, "old").unwrap();
+
+	// A failed download must leave the existing file untouched.
+	let failed = client.download_file_to_path("$HOME/missing.tar", &local_path, |_| ()).await;
+	let after_failure = std::fs::read(&local_path).unwrap();
+
+	let content_type = client.download_file_to_path("$HOME/backup.tar", &local_path, |_| ()).await;
+	let after_success = std::fs::read(&local_path).unwrap();
+	let files : Vec<_> = std::fs::read_dir(&directory).unwrap().map(|x| x.unwrap().file_name()).collect();
+	std::fs::remove_dir_all(&directory).unwrap();
+
+	assert!(let Err(abbrws::Error::FileService(_)) = failed);
+	assert!(after_failure == b"old");
+	assert!(content_type.unwrap() == mime::APPLICATION_OCTET_STREAM);
+	assert!(after_success == mock::contents());
+	assert!(files == ["backup.tar"]);
+}
@@ -0,0 +1,78 @@
+use assert2::assert;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+const SECOND_PAGE: &str = r#"{
+	"_links": { "base": { "href": "http://192.168.0.5:80/rw/elog/0/" } },
+	"_embedded": { "_state": [
+		{ "_type": "elog-message-li", "_title": "/rw/elog/0/10039", "msgtype": "1", "code": "10010", "title": "Motors OFF state" }
+	] }
+}"#;
+
+const LOOP_PAGE: &str = r#"{
+	"_links": { "base": { "href": "http://192.168.0.5:80/rw/elog/1/" }, "next": { "href": "/rw/elog/1?lang=en&json=1" } },
+	"_embedded": { "_state": [
+		{ "_type": "elog-message-li", "_title": "/rw/elog/1/20", "msgtype": "1", "code": "10010", "title": "Motors OFF state" },
+		{ "_type": "elog-message-li", "_title": "/rw/elog/1/21", "msgtype": "1", "code": "10011", "title": "Motors ON state" }
+	] }
+}"#;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match (request.path.as_str(), request.query.as_str()) {
+		("/rw/elog/0", "lang=en&json=1") => mock::json(StatusCode::OK, &include_bytes!("../../samples/elog.json")[..]),
+		("/rw/elog/0/", "lang=en&start=3&limit=3&json=1") => mock::json(StatusCode::OK, SECOND_PAGE),
+		("/rw/elog/1", "lang=en&json=1") => mock::json(StatusCode::OK, LOOP_PAGE),
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+#[tokio::test]
+async fn test_get_event_log_pages() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let messages = client.get_event_log(0).await.unwrap();
+	let sequence_numbers : Vec<_> = messages.iter().map(|x| x.sequence_number).collect();
+	assert!(sequence_numbers == [10042, 10041, 10040, 10039]);
+	assert!(server.requests().len() == 2);
+}
+
+#[tokio::test]
+async fn test_get_event_log_since() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let filter = abbrws::ElogFilter { min_severity: None, since: Some(10040) };
+	let messages = client.get_event_log_filtered(0, &filter).await.unwrap();
+	let sequence_numbers : Vec<_> = messages.iter().map(|x| x.sequence_number).collect();
+	assert!(sequence_numbers == [10042, 10041]);
+
+	// The first page already reached the requested sequence number, so the second page is never requested.
+	assert!(server.requests().len() == 1);
+}
+
+#[tokio::test]
+async fn test_get_event_log_severity() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let filter = abbrws::ElogFilter { min_severity: Some(abbrws::ElogSeverity::Error), since: None };
+	let messages = client.get_event_log_filtered(0, &filter).await.unwrap();
+	assert!(messages.len() == 1);
+	assert!(messages[0].title == "Motion supervision");
+}
+
+#[tokio::test]
+async fn test_get_event_log_next_link_loop() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	// The page links to itself, and the messages are not sorted from new to old, so the filter is never exhausted.
+	let filter = abbrws::ElogFilter { min_severity: None, since: Some(5) };
+	let messages = client.get_event_log_filtered(1, &filter).await.unwrap();
+	let sequence_numbers : Vec<_> = messages.iter().map(|x| x.sequence_number).collect();
+	assert!(sequence_numbers == [20, 21]);
+	assert!(server.requests().len() == 1);
+}
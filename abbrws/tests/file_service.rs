@@ -0,0 +1,154 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+const BACKUP_DIR: &str = r#"{
+	"_links": { "base": { "href": "http://127.0.0.1/fileservice/$HOME/backup/" } },
+	"_embedded": { "_state": [
+		{ "_type": "fs-dir", "_title": "logs" },
+		{ "_type": "fs-file", "_title": "config.cfg", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2022-06-01 T 10:00:00", "fs-size": "120", "fs-readonly": "false" }
+	] }
+}"#;
+
+const LOGS_DIR: &str = r#"{
+	"_links": { "base": { "href": "http://127.0.0.1/fileservice/$HOME/backup/logs/" } },
+	"_embedded": { "_state": [
+		{ "_type": "fs-file", "_title": "run.log", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2022-06-01 T 10:00:00", "fs-size": "4096", "fs-readonly": "false" }
+	] }
+}"#;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match (&request.method, request.path.as_str()) {
+		(&Method::GET, "/fileservice/$HOME/backup/") => mock::json(StatusCode::OK, BACKUP_DIR),
+		(&Method::GET, "/fileservice/$HOME/backup/logs/") => mock::json(StatusCode::OK, LOGS_DIR),
+		(&Method::DELETE, "/fileservice/$HOME/missing.txt/") => mock::empty(StatusCode::NOT_FOUND),
+		(&Method::DELETE, "/fileservice/$HOME/locked.txt/") => mock::error(StatusCode::FORBIDDEN, -1073442815, "Access denied"),
+		(&Method::DELETE, _) => mock::empty(StatusCode::NO_CONTENT),
+		(&Method::POST, "/fileservice/$HOME/config.cfg/") => mock::empty(StatusCode::NO_CONTENT),
+		(&Method::POST, "/fileservice/$HOME/other.cfg/") => mock::error(StatusCode::BAD_REQUEST, -1073442809, "File already exists"),
+		(&Method::POST, "/fileservice/$HOME/") => mock::error(StatusCode::BAD_REQUEST, -1073442809, "Directory already exists"),
+		(&Method::PUT, "/fileservice/$HOME/locked.txt/") => mock::error(StatusCode::BAD_REQUEST, -1073442815, "Access denied"),
+		(&Method::PUT, "/fileservice/$HOME/forbidden.txt/") => mock::error(StatusCode::FORBIDDEN, -1073445887, "Not allowed"),
+		(&Method::PUT, "/fileservice/$HOME/notempty.txt/") => mock::error(StatusCode::BAD_REQUEST, -1073445887, "Directory not empty"),
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+fn kind_of(error: abbrws::Error) -> Option<abbrws::FileServiceErrorKind> {
+	match error {
+		abbrws::Error::FileService(e) => Some(e.kind),
+		_ => None,
+	}
+}
+
+#[tokio::test]
+async fn test_delete_file() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.delete_file("$HOME/config.cfg").await.unwrap();
+	let requests = server.requests();
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == Method::DELETE);
+	assert!(requests[0].path == "/fileservice/$HOME/config.cfg/");
+}
+
+#[tokio::test]
+async fn test_delete_errors() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let error = client.delete_file("$HOME/missing.txt").await.unwrap_err();
+	assert!(kind_of(error) == Some(abbrws::FileServiceErrorKind::NotFound));
+
+	let error = client.delete_file("$HOME/locked.txt").await.unwrap_err();
+	assert!(kind_of(error) == Some(abbrws::FileServiceErrorKind::ReadOnly));
+}
+
+#[tokio::test]
+async fn test_error_codes() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	// The kind is determined by the error code, regardless of the HTTP status.
+	let error = client.upload_file("$HOME/locked.txt", mime::TEXT_PLAIN, "x").await.unwrap_err();
+	assert!(kind_of(error) == Some(abbrws::FileServiceErrorKind::ReadOnly));
+
+	let error = client.create_directory("$HOME/logs").await.unwrap_err();
+	assert!(let abbrws::Error::FileService(abbrws::FileServiceError { kind: abbrws::FileServiceErrorKind::AlreadyExists, .. }) = &error);
+	assert!(error.to_string().starts_with("$HOME/logs: already exists: "));
+
+	// Unknown codes are not mapped, even if the status or message looks familiar.
+	let error = client.upload_file("$HOME/forbidden.txt", mime::TEXT_PLAIN, "x").await.unwrap_err();
+	assert!(let abbrws::Error::RemoteFailure(abbrws::RemoteFailureError { http_status: StatusCode::FORBIDDEN, .. }) = error);
+	let error = client.upload_file("$HOME/notempty.txt", mime::TEXT_PLAIN, "x").await.unwrap_err();
+	assert!(let abbrws::Error::RemoteFailure(_) = error);
+
+	// Only a 404 status without known code is mapped by status.
+	let error = client.download_file("$HOME/missing.txt").await.unwrap_err();
+	assert!(kind_of(error) == Some(abbrws::FileServiceErrorKind::NotFound));
+}
+
+#[tokio::test]
+async fn test_delete_directory_recursive() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.delete_directory("$HOME/backup", true).await.unwrap();
+
+	// The contents must be deleted before the directory containing them.
+	let deleted : Vec<_> = server.requests().into_iter()
+		.filter(|x| x.method == Method::DELETE)
+		.map(|x| x.path)
+		.collect();
+	assert!(deleted == [
+		"/fileservice/$HOME/backup/config.cfg/",
+		"/fileservice/$HOME/backup/logs/run.log/",
+		"/fileservice/$HOME/backup/logs/",
+		"/fileservice/$HOME/backup/",
+	]);
+}
+
+#[tokio::test]
+async fn test_delete_directory_not_empty() {
+	let server = MockServer::start(|request| {
+		match request.method {
+			Method::DELETE => mock::error(StatusCode::BAD_REQUEST, -1073442810, "Directory not empty"),
+			_ => mock::empty(StatusCode::NOT_FOUND),
+		}
+	});
+	let mut client = server.client();
+
+	let error = client.delete_directory("$HOME/backup", false).await.unwrap_err();
+	assert!(let abbrws::Error::FileService(abbrws::FileServiceError { kind: abbrws::FileServiceErrorKind::NotEmpty, .. }) = &error);
+	assert!(error.to_string().starts_with("$HOME/backup: directory not empty: "));
+	assert!(server.requests().len() == 1);
+}
+
+#[tokio::test]
+async fn test_rename() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.rename("$HOME/config.cfg", "$HOME/backup/config.cfg").await.unwrap();
+	let requests = server.requests();
+	assert!(requests[0].method == Method::POST);
+	assert!(requests[0].body == b"fs-newname=$HOME/backup/config.cfg&fs-action=rename");
+}
+
+#[tokio::test]
+async fn test_copy() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.copy("$HOME/config.cfg", "$HOME/copy.cfg", true).await.unwrap();
+	let requests = server.requests();
+	assert!(requests[0].method == Method::POST);
+	assert!(requests[0].body == b"fs-newname=$HOME/copy.cfg&fs-action=copy&fs-overwrite=true");
+
+	let error = client.copy("$HOME/other.cfg", "$HOME/copy.cfg", false).await.unwrap_err();
+	assert!(kind_of(error) == Some(abbrws::FileServiceErrorKind::AlreadyExists));
+}
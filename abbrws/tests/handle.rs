@@ -0,0 +1,63 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	if request.method != Method::GET {
+		return mock::empty(StatusCode::NO_CONTENT);
+	}
+	match request.path.as_str() {
+		"/rw/iosystem/signals/Local/PANEL/SS2/" => mock::json(StatusCode::OK, &include_bytes!("../../samples/good_signal.json")[..]),
+		"/rw/iosystem/signals/Local/DRV_1/GO1/" => {
+			let body = include_str!("../../samples/good_signal.json")
+				.replace(r#""type":"DI""#, r#""type":"GO""#)
+				.replace(r#""lvalue":"1""#, r#""lvalue":"18446744073709551615""#)
+				.replace(r#""pvalue": "1""#, r#""pvalue": "0""#);
+			mock::json(StatusCode::OK, body)
+		},
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+#[tokio::test]
+async fn test_digital_input() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let input = client.digital_input("Local/PANEL/SS2").await.unwrap();
+	assert!(input.name() == "Local/PANEL/SS2");
+	assert!(let Ok(true) = input.get(&mut client).await);
+}
+
+#[tokio::test]
+async fn test_group_output() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let output = client.group_output("Local/DRV_1/GO1").await.unwrap();
+	assert!(let Ok(u64::MAX) = output.get(&mut client).await);
+	assert!(let Ok(()) = output.set(&mut client, 12).await);
+
+	let requests = server.requests();
+	let last = requests.last().unwrap();
+	assert!(last.method == Method::POST);
+	assert!(last.path == "/rw/iosystem/signals/Local/DRV_1/GO1/");
+	assert!(last.body == b"lvalue=12");
+}
+
+#[tokio::test]
+async fn test_kind_mismatch() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let result = client.digital_output("Local/PANEL/SS2").await;
+	assert!(let Err(abbrws::Error::SignalKindMismatch(_)) = &result);
+	if let Err(abbrws::Error::SignalKindMismatch(error)) = result {
+		assert!(error.signal == "Local/PANEL/SS2");
+		assert!(error.expected == abbrws::SignalKind::DigitalOutput);
+		assert!(error.actual == abbrws::SignalKind::DigitalInput);
+	}
+}
@@ -0,0 +1,42 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match (&request.method, request.path.as_str()) {
+		(&Method::GET, "/rw/iosystem/devices/Local/PANEL") => mock::json(StatusCode::OK, &include_bytes!("../../samples/io_device.json")[..]),
+		(&Method::POST, "/rw/iosystem/devices/Local/PANEL") => mock::empty(StatusCode::NO_CONTENT),
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+#[tokio::test]
+async fn test_get_io_device() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let device = client.get_io_device("Local/PANEL").await.unwrap();
+	assert!(device.title == "Local/PANEL");
+	assert!(device.logical_state == abbrws::DeviceState::Enabled);
+}
+
+#[tokio::test]
+async fn test_set_io_device_state() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.set_io_device_state("Local/PANEL", abbrws::DeviceState::Disabled).await.unwrap();
+
+	let requests = server.requests();
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == Method::POST);
+	assert!(requests[0].query == "action=set&json=1");
+	assert!(requests[0].headers[hyper::header::CONTENT_TYPE] == "application/x-www-form-urlencoded");
+	assert!(requests[0].body == b"lstate=disabled");
+
+	let result = client.set_io_device_state("Local/MISSING", abbrws::DeviceState::Enabled).await;
+	assert!(let Err(abbrws::Error::RemoteFailure(abbrws::RemoteFailureError { http_status: StatusCode::NOT_FOUND, .. })) = result);
+}
@@ -0,0 +1,57 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+#[tokio::test]
+async fn test_release_on_drop() {
+	let server = MockServer::start(|_request| mock::empty(StatusCode::NO_CONTENT));
+	let mut client = server.client();
+
+	let guard = client.request_mastership(abbrws::MastershipDomain::Rapid).await.unwrap();
+	drop(guard);
+
+	let requests = server.wait_for_requests(2).await;
+	assert!(requests[0].method == Method::POST);
+	assert!(requests[0].path == "/rw/mastership/rapid");
+	assert!(requests[0].query == "action=request&json=1");
+	assert!(requests[1].method == Method::POST);
+	assert!(requests[1].path == "/rw/mastership/rapid");
+	assert!(requests[1].query == "action=release&json=1");
+}
+
+#[tokio::test]
+async fn test_explicit_release() {
+	let server = MockServer::start(|_request| mock::empty(StatusCode::NO_CONTENT));
+	let mut client = server.client();
+
+	let guard = client.request_mastership(abbrws::MastershipDomain::Motion).await.unwrap();
+	assert!(let Ok(()) = guard.release().await);
+
+	let requests = server.requests();
+	assert!(requests.len() == 2);
+	assert!(requests[1].path == "/rw/mastership/motion");
+	assert!(requests[1].query == "action=release&json=1");
+}
+
+#[tokio::test]
+async fn test_mastership_held() {
+	let server = MockServer::start(|request| {
+		if request.method == Method::GET {
+			mock::json(StatusCode::OK, &include_bytes!("../../samples/mastership.json")[..])
+		} else {
+			mock::error(StatusCode::FORBIDDEN, -1073445865, "mastership held by other client")
+		}
+	});
+	let mut client = server.client();
+
+	let result = client.request_mastership(abbrws::MastershipDomain::Rapid).await;
+	assert!(let Err(abbrws::Error::MastershipHeld(_)) = &result);
+	if let Err(abbrws::Error::MastershipHeld(error)) = result {
+		assert!(error.holder == abbrws::MastershipHolder::Remote);
+		assert!(error.error.http_status == StatusCode::FORBIDDEN);
+		assert!(error.error.code == Some(-1073445865i32 as u32));
+	}
+}
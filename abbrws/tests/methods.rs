@@ -0,0 +1,109 @@
+use assert2::assert;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+/// Authenticate requests like the controller, and reply based on the method and path.
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	if !mock::check_digest(request) {
+		return mock::challenge();
+	}
+
+	use hyper::Method;
+	match (&request.method, request.path.as_str()) {
+		(&Method::GET, "/fileservice/") => mock::json(StatusCode::OK, r#"{"_embedded": {"_state": []}}"#),
+		(&Method::GET, "/fileservice/$HOME/notes.txt/") => hyper::Response::builder()
+			.header(hyper::header::CONTENT_TYPE, "text/plain")
+			.body("hello".into())
+			.unwrap(),
+		(&Method::PUT, "/fileservice/$HOME/notes.txt/") => mock::empty(StatusCode::CREATED),
+		(&Method::POST, "/fileservice/$HOME/") => mock::empty(StatusCode::CREATED),
+		(&Method::POST, "/rw/iosystem/signals/Local/DRV_1/DO1/") => mock::empty(StatusCode::NO_CONTENT),
+		_ => mock::empty(StatusCode::METHOD_NOT_ALLOWED),
+	}
+}
+
+/// Get the authenticated requests received by the server, without the 401 challenges.
+fn authenticated_requests(server: &MockServer) -> Vec<mock::RecordedRequest> {
+	server.requests().into_iter().filter(mock::check_digest).collect()
+}
+
+#[tokio::test]
+async fn test_upload_file_uses_put() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.upload_file("$HOME/notes.txt", mime::TEXT_PLAIN, "hello").await.unwrap();
+
+	let requests = authenticated_requests(&server);
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == hyper::Method::PUT);
+	assert!(requests[0].path == "/fileservice/$HOME/notes.txt/");
+	assert!(requests[0].query == "json=1");
+	assert!(requests[0].headers[hyper::header::CONTENT_TYPE] == "text/plain");
+	assert!(requests[0].body == b"hello");
+}
+
+#[tokio::test]
+async fn test_upload_file_from_uses_put() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.upload_file_from("$HOME/notes.txt", mime::TEXT_PLAIN, &b"hello"[..], Some(5), |_| ()).await.unwrap();
+
+	let requests = authenticated_requests(&server);
+	assert!(requests.len() == 2);
+	assert!(requests[0].method == hyper::Method::GET);
+	assert!(requests[0].body.is_empty());
+	assert!(requests[1].method == hyper::Method::PUT);
+	assert!(requests[1].path == "/fileservice/$HOME/notes.txt/");
+	assert!(requests[1].headers[hyper::header::CONTENT_TYPE] == "text/plain");
+	assert!(requests[1].headers[hyper::header::CONTENT_LENGTH] == "5");
+	assert!(requests[1].body == b"hello");
+}
+
+#[tokio::test]
+async fn test_download_file_uses_get() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let (content_type, data) = client.download_file("$HOME/notes.txt").await.unwrap();
+	assert!(content_type == mime::TEXT_PLAIN);
+	assert!(data == b"hello");
+
+	let requests = authenticated_requests(&server);
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == hyper::Method::GET);
+	assert!(requests[0].body.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_directory_uses_post() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.create_directory("$HOME/logs").await.unwrap();
+
+	let requests = authenticated_requests(&server);
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == hyper::Method::POST);
+	assert!(requests[0].path == "/fileservice/$HOME/");
+	assert!(requests[0].headers[hyper::header::CONTENT_TYPE] == "application/x-www-form-urlencoded");
+	assert!(requests[0].body == b"fs-newname=logs&fs-action=create");
+}
+
+#[tokio::test]
+async fn test_set_signal_uses_post() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	client.set_signal("Local/DRV_1/DO1", abbrws::SignalValue::Binary(true)).await.unwrap();
+
+	let requests = authenticated_requests(&server);
+	assert!(requests.len() == 1);
+	assert!(requests[0].method == hyper::Method::POST);
+	assert!(requests[0].query == "action=set&json=1");
+	assert!(requests[0].headers[hyper::header::CONTENT_TYPE] == "application/x-www-form-urlencoded");
+	assert!(requests[0].body == b"lvalue=1");
+}
@@ -0,0 +1,221 @@
+//! A mock RWS server that records requests and replies with canned responses.
+
+#![allow(dead_code)]
+
+use hyper::Body;
+use hyper::upgrade::Upgraded;
+use hyper::Response;
+use hyper::StatusCode;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+
+/// A request received by the mock server.
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+	pub method: hyper::Method,
+
+	/// The path of the request, with duplicate leading slashes removed.
+	pub path: String,
+
+	pub query: String,
+
+	/// The request target exactly as sent by the client.
+	pub target: String,
+
+	pub headers: hyper::HeaderMap,
+	pub body: Vec<u8>,
+}
+
+pub struct MockServer {
+	pub address: SocketAddr,
+	requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+	/// Start a mock server on a random local port.
+	///
+	/// The `respond` function is called for each request to create a response.
+	pub fn start<F>(respond: F) -> Self
+	where
+		F: Fn(&RecordedRequest) -> Response<Body> + Send + Sync + 'static,
+	{
+		Self::start_with_websocket(respond, |_request, _socket| async {})
+	}
+
+	/// Start a mock server that also accepts WebSocket connections.
+	///
+	/// If `respond` returns a `101 Switching Protocols` response (see [`websocket_accept`]),
+	/// the `on_websocket` function is called with the upgraded connection.
+	pub fn start_with_websocket<F, W, Fut>(respond: F, on_websocket: W) -> Self
+	where
+		F: Fn(&RecordedRequest) -> Response<Body> + Send + Sync + 'static,
+		W: Fn(RecordedRequest, WebSocketStream<Upgraded>) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = ()> + Send + 'static,
+	{
+		let respond = Arc::new(respond);
+		let on_websocket = Arc::new(on_websocket);
+		let requests = Arc::new(Mutex::new(Vec::new()));
+		let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let address = listener.local_addr().unwrap();
+
+		let make_service = {
+			let requests = requests.clone();
+			hyper::service::make_service_fn(move |_connection| {
+				let requests = requests.clone();
+				let respond = respond.clone();
+				let on_websocket = on_websocket.clone();
+				async move {
+					Ok::<_, Infallible>(hyper::service::service_fn(move |mut request: hyper::Request<Body>| {
+						let requests = requests.clone();
+						let respond = respond.clone();
+						let on_websocket = on_websocket.clone();
+						async move {
+							let upgrade = hyper::upgrade::on(&mut request);
+							let (parts, body) = request.into_parts();
+							let body = hyper::body::to_bytes(body).await.unwrap();
+							let request = RecordedRequest {
+								method: parts.method,
+								path: format!("/{}", parts.uri.path().trim_start_matches('/')),
+								query: parts.uri.query().unwrap_or_default().to_string(),
+								target: parts.uri.path_and_query().map(|x| x.to_string()).unwrap_or_default(),
+								headers: parts.headers,
+								body: body.to_vec(),
+							};
+							let response = respond(&request);
+							if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+								let request = request.clone();
+								tokio::spawn(async move {
+									let upgraded = upgrade.await.unwrap();
+									let socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+									on_websocket(request, socket).await;
+								});
+							}
+							requests.lock().unwrap().push(request);
+							Ok::<_, Infallible>(response)
+						}
+					}))
+				}
+			})
+		};
+
+		tokio::spawn(hyper::Server::from_tcp(listener).unwrap().serve(make_service));
+		Self { address, requests }
+	}
+
+	/// Create a client that talks to the mock server.
+	pub fn client(&self) -> abbrws::Client {
+		abbrws::Client::new(self.address.to_string(), "Default User", "robotics").unwrap()
+	}
+
+	/// Get all requests received so far.
+	pub fn requests(&self) -> Vec<RecordedRequest> {
+		self.requests.lock().unwrap().clone()
+	}
+
+	/// Wait until the server received at least `count` requests, or panic after a timeout.
+	pub async fn wait_for_requests(&self, count: usize) -> Vec<RecordedRequest> {
+		for _ in 0..100 {
+			let requests = self.requests();
+			if requests.len() >= count {
+				return requests;
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+		panic!("timeout waiting for {} requests, got {}", count, self.requests().len());
+	}
+}
+
+/// The digest challenge sent by the mock server, like that of the controller.
+const CHALLENGE: &str = r#"Digest realm="validusers@robapi.abb", nonce="8c47b1b25a6f9d53", qop="auth""#;
+
+/// Create a 401 Unauthorized response with a digest challenge.
+pub fn challenge() -> Response<Body> {
+	Response::builder()
+		.status(StatusCode::UNAUTHORIZED)
+		.header(hyper::header::CONTENT_TYPE, "text/plain")
+		.header(hyper::header::WWW_AUTHENTICATE, CHALLENGE)
+		.body(Body::empty())
+		.unwrap()
+}
+
+/// Check the digest response of a request for the method and target it was actually sent with.
+///
+/// The credentials must be those used by [`MockServer::client`].
+pub fn check_digest(request: &RecordedRequest) -> bool {
+	let header = match request.headers.get(hyper::header::AUTHORIZATION).map(|x| x.to_str()) {
+		Some(Ok(x)) => x,
+		_ => return false,
+	};
+	let header = match digest_auth::AuthorizationHeader::parse(header) {
+		Ok(x) => x,
+		Err(_) => return false,
+	};
+	if header.uri != request.target {
+		return false;
+	}
+
+	let context = digest_auth::AuthContext {
+		username: "Default User".into(),
+		password: "robotics".into(),
+		uri: request.target.as_str().into(),
+		method: digest_auth::HttpMethod(request.method.as_str().to_string().into()),
+		body: None,
+		cnonce: None,
+	};
+	let mut expected = header.clone();
+	expected.digest(&context);
+	expected.response == header.response
+}
+
+/// Contents of a large mock file: large enough to be sent in multiple chunks.
+pub fn contents() -> Vec<u8> {
+	(0..1_000_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+/// Create a JSON response.
+pub fn json(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+	Response::builder()
+		.status(status)
+		.header(hyper::header::CONTENT_TYPE, "application/json")
+		.body(body.into())
+		.unwrap()
+}
+
+/// Create an RWS error response with the given error code.
+pub fn error(status: StatusCode, code: i32, message: &str) -> Response<Body> {
+	let body = format!(
+		r#"{{"_links":{{"base": {{ "href": "http://127.0.0.1:80/" }}}},"_embedded" :{{ "status" : {{"code":{}, "msg": "{}"}}, "_state":[  ] }}}}"#,
+		code,
+		message,
+	);
+	json(status, body)
+}
+
+/// Create a response without body.
+pub fn empty(status: StatusCode) -> Response<Body> {
+	Response::builder()
+		.status(status)
+		.header(hyper::header::CONTENT_TYPE, "text/plain")
+		.body(Body::empty())
+		.unwrap()
+}
+
+/// Accept a WebSocket upgrade request with the given subprotocol.
+pub fn websocket_accept(request: &RecordedRequest, protocol: &str) -> Response<Body> {
+	let key = request.headers.get(hyper::header::SEC_WEBSOCKET_KEY).unwrap();
+	Response::builder()
+		.status(StatusCode::SWITCHING_PROTOCOLS)
+		.header(hyper::header::CONNECTION, "Upgrade")
+		.header(hyper::header::UPGRADE, "websocket")
+		.header(hyper::header::SEC_WEBSOCKET_ACCEPT, derive_accept_key(key.as_bytes()))
+		.header(hyper::header::SEC_WEBSOCKET_PROTOCOL, protocol)
+		.body(Body::empty())
+		.unwrap()
+}
@@ -0,0 +1,130 @@
+use assert2::assert;
+use futures_util::StreamExt;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+fn signal_page(names: &[&str], next: Option<&str>) -> String {
+	let items : Vec<_> = names.iter()
+		.map(|name| format!(r#"{{ "_type": "ios-signal-li", "_title": "Local/DRV_1/{}", "name": "{}", "type": "DO", "category": "", "lvalue": 0, "lstate": "not simulated" }}"#, name, name))
+		.collect();
+	let next = next.map(|x| format!(r#", "next": {{ "href": "{}" }}"#, x)).unwrap_or_default();
+	format!(
+		r#"{{ "_links": {{ "base": {{ "href": "http://192.168.0.5:80/rw/iosystem/" }}{} }}, "_embedded": {{ "_state": [{}] }} }}"#,
+		next,
+		items.join(","),
+	)
+}
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match (request.path.as_str(), request.query.as_str()) {
+		("/rw/iosystem/signals", "start=0&limit=100&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["A", "B"], Some("signals?start=2&limit=2&json=1")))
+		},
+		("/rw/iosystem/signals", "start=2&limit=2&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["C", "D"], Some("/rw/iosystem/signals?start=4&limit=2&json=1")))
+		},
+		("/rw/iosystem/signals", "start=4&limit=2&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["E"], None))
+		},
+		("/rw/iosystem/signals", "category=safety&start=0&limit=100&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["S"], None))
+		},
+		("/rw/iosystem/signals", "type=DI&device=DRV_1&start=0&limit=2&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["A", "B"], Some("signals?type=DI&device=DRV_1&start=2&limit=2&json=1")))
+		},
+		("/rw/iosystem/signals", "category=loop&start=0&limit=100&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["L1"], Some("signals?category=loop&start=1&limit=100&json=1")))
+		},
+		("/rw/iosystem/signals", "category=loop&start=1&limit=100&json=1") => {
+			mock::json(StatusCode::OK, signal_page(&["L2"], Some("signals?category=loop&start=0&limit=100&json=1")))
+		},
+		("/fileservice/$HOME/", "start=0&limit=100&json=1") => mock::json(StatusCode::OK, r#"{
+			"_links": { "base": { "href": "http://192.168.0.5:80/fileservice/$HOME/" }, "next": { "href": "?start=1&limit=1&json=1" } },
+			"_embedded": { "_state": [
+				{ "_type": "fs-dir", "_title": "backup" }
+			] }
+		}"#),
+		("/fileservice/$HOME/", "start=1&limit=1&json=1") => mock::json(StatusCode::OK, r#"{
+			"_links": { "base": { "href": "http://192.168.0.5:80/fileservice/$HOME/" } },
+			"_embedded": { "_state": [
+				{ "_type": "fs-file", "_title": "main.mod", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2022-06-01 T 10:00:00", "fs-size": "1234", "fs-readonly": "false" }
+			] }
+		}"#),
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+#[tokio::test]
+async fn test_get_signals_follows_next_links() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let signals = client.get_signals().await.unwrap();
+	let titles : Vec<_> = signals.iter().map(|x| x.title.as_str()).collect();
+	assert!(titles == ["Local/DRV_1/A", "Local/DRV_1/B", "Local/DRV_1/C", "Local/DRV_1/D", "Local/DRV_1/E"]);
+	assert!(server.requests().len() == 3);
+}
+
+#[tokio::test]
+async fn test_stream_signals_is_lazy() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let first : Vec<_> = client.stream_signals().take(3).collect().await;
+	assert!(first.len() == 3);
+	assert!(first.iter().all(|x| x.is_ok()));
+
+	// Only the pages needed for the first three signals were downloaded.
+	assert!(server.requests().len() == 2);
+}
+
+#[tokio::test]
+async fn test_query_signals() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let signals = client.query_signals(&abbrws::SignalQuery::new().category("safety")).await.unwrap();
+	assert!(signals.len() == 1);
+	assert!(signals[0].title == "Local/DRV_1/S");
+}
+
+#[tokio::test]
+async fn test_query_signals_limit() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let query = abbrws::SignalQuery::new()
+		.kind(abbrws::SignalKind::DigitalInput)
+		.device("DRV_1")
+		.limit(2);
+	let signals = client.query_signals(&query).await.unwrap();
+	assert!(signals.len() == 2);
+
+	// The limit was reached on the first page, so the next page is not requested.
+	assert!(server.requests().len() == 1);
+}
+
+#[tokio::test]
+async fn test_next_link_loop() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	// The second page links back to the first, which must not be requested again.
+	let signals = client.query_signals(&abbrws::SignalQuery::new().category("loop")).await.unwrap();
+	let titles : Vec<_> = signals.iter().map(|x| x.title.as_str()).collect();
+	assert!(titles == ["Local/DRV_1/L1", "Local/DRV_1/L2"]);
+	assert!(server.requests().len() == 2);
+}
+
+#[tokio::test]
+async fn test_list_files_follows_next_links() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let entries = client.list_files("$HOME").await.unwrap();
+	assert!(entries.len() == 2);
+	assert!(let abbrws::DirEntry::Directory(_) = &entries[0]);
+	assert!(let abbrws::DirEntry::File(_) = &entries[1]);
+}
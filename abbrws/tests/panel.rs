@@ -0,0 +1,37 @@
+use assert2::assert;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+#[tokio::test]
+async fn test_set_panel() {
+	let server = MockServer::start(|_| mock::empty(StatusCode::NO_CONTENT));
+	let mut client = server.client();
+
+	client.set_controller_state(abbrws::ControllerState::MotorsOn).await.unwrap();
+	client.set_operation_mode(abbrws::OperationMode::ManualReduced).await.unwrap();
+	client.set_speed_ratio(100).await.unwrap();
+
+	let bodies : Vec<_> = server.requests().into_iter().map(|x| x.body).collect();
+	assert!(bodies == [&b"ctrl-state=motoron"[..], b"opmode=man", b"speed-ratio=100"]);
+}
+
+#[tokio::test]
+async fn test_set_panel_invalid() {
+	let server = MockServer::start(|_| mock::empty(StatusCode::NO_CONTENT));
+	let mut client = server.client();
+
+	let result = client.set_controller_state(abbrws::ControllerState::EmergencyStop).await;
+	assert!(let Err(abbrws::Error::InvalidPanelSetting(abbrws::InvalidPanelSettingError::ControllerState(abbrws::ControllerState::EmergencyStop))) = result);
+
+	let result = client.set_operation_mode(abbrws::OperationMode::AutoChange).await;
+	assert!(let Err(abbrws::Error::InvalidPanelSetting(abbrws::InvalidPanelSettingError::OperationMode(abbrws::OperationMode::AutoChange))) = result);
+
+	let error = client.set_speed_ratio(101).await.unwrap_err();
+	assert!(let abbrws::Error::InvalidPanelSetting(abbrws::InvalidPanelSettingError::SpeedRatio(101)) = &error);
+	assert!(error.to_string() == "speed ratio must be between 0 and 100, got 101");
+
+	// Invalid settings must be rejected without contacting the controller.
+	assert!(server.requests().is_empty());
+}
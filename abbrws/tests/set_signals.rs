@@ -0,0 +1,96 @@
+use assert2::assert;
+use hyper::StatusCode;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+mod mock;
+use mock::MockServer;
+
+#[tokio::test]
+async fn test_set_signals() {
+	let server = MockServer::start(|request| {
+		if request.path.contains("/Missing/") {
+			mock::error(StatusCode::BAD_REQUEST, -1073445879, "signal not found")
+		} else if request.headers.contains_key(hyper::header::COOKIE) {
+			mock::empty(StatusCode::NO_CONTENT)
+		} else {
+			let mut response = mock::empty(StatusCode::NO_CONTENT);
+			response.headers_mut().insert(hyper::header::SET_COOKIE, "-http-session-=1; path=/".parse().unwrap());
+			response
+		}
+	});
+	let mut client = server.client();
+
+	let results = client.set_signals(&[
+		("Local/DRV_1/GO1", abbrws::SignalValue::Group(12)),
+		("Local/DRV_1/Missing", abbrws::SignalValue::Binary(true)),
+		("Local/DRV_1/DO1", abbrws::SignalValue::Binary(true)),
+	]).await;
+
+	assert!(results.len() == 3);
+	assert!(let Ok(()) = &results[0]);
+	assert!(let Err(abbrws::Error::RemoteFailure(_)) = &results[1]);
+	assert!(let Ok(()) = &results[2]);
+
+	// The first signal is written first, and the session from that write is used for the others.
+	let mut requests : Vec<_> = server.requests().into_iter()
+		.map(|x| (x.path, String::from_utf8(x.body).unwrap(), x.headers.contains_key(hyper::header::COOKIE)))
+		.collect();
+	requests[1..].sort();
+	assert!(requests == [
+		("/rw/iosystem/signals/Local/DRV_1/GO1/".to_string(), "lvalue=12".to_string(), false),
+		("/rw/iosystem/signals/Local/DRV_1/DO1/".to_string(), "lvalue=1".to_string(), true),
+		("/rw/iosystem/signals/Local/DRV_1/Missing/".to_string(), "lvalue=1".to_string(), true),
+	]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_set_signals_concurrent() {
+	let in_flight = Arc::new(AtomicUsize::new(0));
+	let max_in_flight = Arc::new(AtomicUsize::new(0));
+	let server = MockServer::start({
+		let in_flight = in_flight.clone();
+		let max_in_flight = max_in_flight.clone();
+		move |request| {
+			if !mock::check_digest(request) {
+				return mock::challenge();
+			}
+			let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+			max_in_flight.fetch_max(current, Ordering::SeqCst);
+			std::thread::sleep(std::time::Duration::from_millis(50));
+			in_flight.fetch_sub(1, Ordering::SeqCst);
+			mock::empty(StatusCode::NO_CONTENT)
+		}
+	});
+	let mut client = server.client();
+
+	let signals : Vec<_> = (0..5).map(|i| (format!("Local/DRV_1/DO{}", i), abbrws::SignalValue::Binary(true))).collect();
+	let results = client.set_signals(&signals).await;
+	assert!(results.iter().all(|x| x.is_ok()));
+
+	// Only the first write needed an authentication handshake, and the other writes overlapped.
+	let requests = server.requests();
+	assert!(requests.iter().filter(|x| !mock::check_digest(x)).count() == 1);
+	assert!(requests.len() == signals.len() + 1);
+	assert!(max_in_flight.load(Ordering::SeqCst) > 1);
+
+	// Each concurrent write answered the challenge with its own nonce count.
+	let mut nonce_counts : Vec<_> = requests.iter()
+		.filter_map(|x| x.headers.get(hyper::header::AUTHORIZATION))
+		.map(|x| digest_auth::AuthorizationHeader::parse(x.to_str().unwrap()).unwrap().nc)
+		.collect();
+	nonce_counts.sort_unstable();
+	nonce_counts.dedup();
+	assert!(nonce_counts.len() == signals.len());
+}
+
+#[tokio::test]
+async fn test_set_signals_empty() {
+	let server = MockServer::start(|_request| mock::empty(StatusCode::NO_CONTENT));
+	let mut client = server.client();
+
+	let results = client.set_signals::<&str>(&[]).await;
+	assert!(results.is_empty());
+	assert!(server.requests().is_empty());
+}
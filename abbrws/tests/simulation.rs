@@ -0,0 +1,55 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	if request.method == Method::GET {
+		mock::json(StatusCode::OK, &include_bytes!("../../samples/good_signal.json")[..])
+	} else {
+		mock::empty(StatusCode::NO_CONTENT)
+	}
+}
+
+#[tokio::test]
+async fn test_restore_on_drop() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let guard = client.simulate_signal("Local/PANEL/SS2", abbrws::SignalValue::Binary(false)).await.unwrap();
+	assert!(guard.signal() == "Local/PANEL/SS2");
+	drop(guard);
+
+	let requests = server.wait_for_requests(3).await;
+	assert!(requests[0].method == Method::GET);
+	assert!(requests[1].method == Method::POST);
+	assert!(requests[1].path == "/rw/iosystem/signals/Local/PANEL/SS2/");
+	assert!(requests[1].query == "action=set&json=1");
+	assert!(requests[1].body == b"lstate=simulated&lvalue=0");
+	assert!(requests[2].method == Method::POST);
+	assert!(requests[2].path == "/rw/iosystem/signals/Local/PANEL/SS2/");
+	assert!(requests[2].body == b"lstate=not%20simulated");
+}
+
+#[tokio::test]
+async fn test_restore_simulated_value() {
+	let server = MockServer::start(|request| {
+		if request.method == Method::GET {
+			let body = include_str!("../../samples/good_signal.json").replace(r#""lstate": "not simulated""#, r#""lstate": "simulated""#);
+			mock::json(StatusCode::OK, body)
+		} else {
+			mock::empty(StatusCode::NO_CONTENT)
+		}
+	});
+	let mut client = server.client();
+
+	let guard = client.simulate_signal("Local/PANEL/SS2", abbrws::SignalValue::Binary(false)).await.unwrap();
+	assert!(let Ok(()) = guard.restore().await);
+
+	// The signal was already simulated with value 1, so that is restored.
+	let requests = server.requests();
+	assert!(requests.len() == 3);
+	assert!(requests[2].body == b"lstate=simulated&lvalue=1");
+}
@@ -0,0 +1,76 @@
+use assert2::assert;
+use futures_util::SinkExt;
+use futures_util::StreamExt;
+use hyper::StatusCode;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use tokio_tungstenite::tungstenite::Message;
+
+mod mock;
+use mock::MockServer;
+
+fn respond(subscription_count: &AtomicUsize, request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	if request.path == "/rw/iosystem/signals/Local/PANEL/SS2/" {
+		return mock::json(StatusCode::OK, &include_bytes!("../../samples/good_signal.json")[..]);
+	}
+
+	if request.path == "/subscription" {
+		let id = subscription_count.fetch_add(1, Ordering::SeqCst) + 1;
+		let host = request.headers[hyper::header::HOST].to_str().unwrap();
+		return hyper::Response::builder()
+			.status(StatusCode::CREATED)
+			.header(hyper::header::LOCATION, format!("http://{}/poll/{}", host, id))
+			.header(hyper::header::SET_COOKIE, format!("-http-session-={}; path=/", id))
+			.body(hyper::Body::empty())
+			.unwrap();
+	}
+
+	if request.path.starts_with("/poll/") {
+		return mock::websocket_accept(request, "robapi2_subscription");
+	}
+
+	mock::empty(StatusCode::NOT_FOUND)
+}
+
+#[tokio::test]
+async fn test_subscribe_signals() {
+	let subscription_count = AtomicUsize::new(0);
+	let server = MockServer::start_with_websocket(
+		move |request| respond(&subscription_count, request),
+		|request, mut socket| async move {
+			let id : usize = request.path.strip_prefix("/poll/").unwrap().parse().unwrap();
+			let event = include_str!("../../samples/signal_event.xml").replace(
+				r#"<span class="lvalue">0</span>"#,
+				&format!(r#"<span class="lvalue">{}</span>"#, id % 2),
+			);
+			socket.send(Message::Text(event)).await.unwrap();
+			// Simulate an expired session by closing the connection.
+			socket.close(None).await.unwrap();
+		},
+	);
+	let mut client = server.client();
+
+	let subscription = client.subscribe_signals(&["Local/PANEL/SS2"]).await.unwrap();
+	let signals : Vec<_> = subscription.take(2).collect().await;
+
+	assert!(signals.len() == 2);
+	assert!(let Ok(abbrws::Signal { lvalue: abbrws::SignalValue::Binary(true), .. }) = &signals[0]);
+	assert!(let Ok(abbrws::Signal { lvalue: abbrws::SignalValue::Binary(false), .. }) = &signals[1]);
+	assert!(signals[0].as_ref().unwrap().title == "Local/PANEL/SS2");
+
+	// The second event must have come from a new subscription, made after the WebSocket was closed.
+	let requests = server.requests();
+	let subscriptions : Vec<_> = requests.iter()
+		.filter(|x| x.path == "/subscription")
+		.map(|x| String::from_utf8(x.body.clone()).unwrap())
+		.collect();
+	assert!(subscriptions.len() == 2);
+	assert!(subscriptions[0] == "resources=1&1=/rw/iosystem/signals/Local/PANEL/SS2;state&1-p=1");
+
+	let polls : Vec<_> = requests.iter().filter(|x| x.path.starts_with("/poll/")).collect();
+	let header = |request: &mock::RecordedRequest, name| request.headers.get(name).map(|x| x.to_str().unwrap().to_string()).unwrap_or_default();
+	let protocols : Vec<_> = polls.iter().map(|x| header(x, hyper::header::SEC_WEBSOCKET_PROTOCOL)).collect();
+	let cookies : Vec<_> = polls.iter().map(|x| header(x, hyper::header::COOKIE)).collect();
+	assert!(protocols == ["robapi2_subscription", "robapi2_subscription"]);
+	assert!(cookies == ["-http-session-=1", "-http-session-=2"]);
+}
@@ -0,0 +1,133 @@
+use assert2::assert;
+use hyper::Method;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+const DEPLOY_DIR: &str = r#"{
+	"_links": { "base": { "href": "http://127.0.0.1/fileservice/$HOME/deploy/" } },
+	"_embedded": { "_state": [
+		{ "_type": "fs-file", "_title": "main.mod", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2100-01-01 T 00:00:00", "fs-size": "5", "fs-readonly": "false" },
+		{ "_type": "fs-file", "_title": "changed.mod", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2100-01-01 T 00:00:00", "fs-size": "3", "fs-readonly": "false" },
+		{ "_type": "fs-file", "_title": "locked.cfg", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2100-01-01 T 00:00:00", "fs-size": "1", "fs-readonly": "true" },
+		{ "_type": "fs-file", "_title": "stale.mod", "fs-cdate": "2022-06-01 T 10:00:00", "fs-mdate": "2100-01-01 T 00:00:00", "fs-size": "1", "fs-readonly": "false" }
+	] }
+}"#;
+
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	match (&request.method, request.path.as_str()) {
+		(&Method::GET, "/fileservice/$HOME/deploy/") => mock::json(StatusCode::OK, DEPLOY_DIR),
+		(&Method::GET, "/fileservice/") => mock::json(StatusCode::OK, r#"{"_embedded": {"_state": []}}"#),
+		(&Method::GET, "/fileservice/$HOME/deploy/changed.mod/") => hyper::Response::builder()
+			.header(hyper::header::CONTENT_TYPE, "application/octet-stream")
+			.body("new".into())
+			.unwrap(),
+		(&Method::GET, _) => mock::empty(StatusCode::NOT_FOUND),
+		_ => mock::empty(StatusCode::NO_CONTENT),
+	}
+}
+
+/// Create a local directory tree for a test, removing any leftovers from earlier runs.
+fn local_tree(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+	let root = std::env::temp_dir().join(format!("abbrws-sync-test-{}-{}", name, std::process::id()));
+	let _ = std::fs::remove_dir_all(&root);
+	for (path, contents) in files {
+		let path = root.join(path);
+		std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+		std::fs::write(path, contents).unwrap();
+	}
+	root
+}
+
+#[tokio::test]
+async fn test_sync_upload() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+	let local = local_tree("upload", &[
+		("main.mod", "hello"),
+		("changed.mod", "changed"),
+		("locked.cfg", "xx"),
+		("config/sys.cfg", "sys"),
+	]);
+
+	let options = abbrws::SyncOptions { delete: true, utc_offset_s: Some(0) };
+	let plan = client.plan_sync(&local, "$HOME/deploy", abbrws::SyncDirection::Upload, &options).await.unwrap();
+	assert!(plan.actions == [
+		abbrws::SyncAction::Transfer { path: "changed.mod".into(), reason: abbrws::SyncReason::SizeDiffers },
+		abbrws::SyncAction::CreateDirectory("config".into()),
+		abbrws::SyncAction::Transfer { path: "config/sys.cfg".into(), reason: abbrws::SyncReason::Missing },
+		abbrws::SyncAction::Skip { path: "locked.cfg".into(), reason: abbrws::SkipReason::ReadOnly },
+		abbrws::SyncAction::Delete { path: "stale.mod".into(), directory: false },
+	]);
+	assert!(plan.to_string().lines().next() == Some("upload   $HOME/deploy/changed.mod (size differs)"));
+
+	let listed = server.requests().len();
+	client.apply_sync(&plan, |_| ()).await.unwrap();
+	std::fs::remove_dir_all(&local).unwrap();
+
+	let changes : Vec<_> = server.requests()[listed..].iter()
+		.filter(|x| x.method != Method::GET)
+		.map(|x| (x.method.clone(), x.path.clone(), x.body.clone()))
+		.collect();
+	assert!(changes == [
+		(Method::PUT, "/fileservice/$HOME/deploy/changed.mod/".into(), b"changed".to_vec()),
+		(Method::POST, "/fileservice/$HOME/deploy/".into(), b"fs-newname=config&fs-action=create".to_vec()),
+		(Method::PUT, "/fileservice/$HOME/deploy/config/sys.cfg/".into(), b"sys".to_vec()),
+		(Method::DELETE, "/fileservice/$HOME/deploy/stale.mod/".into(), Vec::new()),
+	]);
+}
+
+#[tokio::test]
+async fn test_sync_download() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+	let local = local_tree("download", &[
+		("main.mod", "hello"),
+		("changed.mod", "old!"),
+		("extra.mod", "extra"),
+	]);
+
+	// Without the UTC offset of the controller, only the sizes are compared.
+	let plan = client.plan_sync(&local, "$HOME/deploy", abbrws::SyncDirection::Download, &Default::default()).await.unwrap();
+	assert!(plan.actions == [
+		abbrws::SyncAction::Transfer { path: "changed.mod".into(), reason: abbrws::SyncReason::SizeDiffers },
+		abbrws::SyncAction::Transfer { path: "locked.cfg".into(), reason: abbrws::SyncReason::Missing },
+		abbrws::SyncAction::Transfer { path: "stale.mod".into(), reason: abbrws::SyncReason::Missing },
+	]);
+
+	let options = abbrws::SyncOptions { delete: false, utc_offset_s: Some(0) };
+	let plan = client.plan_sync(&local, "$HOME/deploy", abbrws::SyncDirection::Download, &options).await.unwrap();
+	assert!(plan.actions[2] == abbrws::SyncAction::Transfer { path: "main.mod".into(), reason: abbrws::SyncReason::Newer });
+
+	let transfer = abbrws::SyncPlan { actions: plan.actions[..1].to_vec(), ..plan.clone() };
+	client.apply_sync(&transfer, |_| ()).await.unwrap();
+
+	// A failed download must leave the existing file untouched.
+	let failed = abbrws::SyncPlan { actions: vec![abbrws::SyncAction::Transfer { path: "extra.mod".into(), reason: abbrws::SyncReason::Newer }], ..plan };
+	let result = client.apply_sync(&failed, |_| ()).await;
+
+	let changed = std::fs::read(local.join("changed.mod")).unwrap();
+	let extra = std::fs::read(local.join("extra.mod")).unwrap();
+	let mut files : Vec<_> = std::fs::read_dir(&local).unwrap().map(|x| x.unwrap().file_name()).collect();
+	files.sort();
+	std::fs::remove_dir_all(&local).unwrap();
+	assert!(let Err(abbrws::Error::FileService(_)) = result);
+	assert!(changed == b"new");
+	assert!(extra == b"extra");
+	assert!(files == ["changed.mod", "extra.mod", "main.mod"]);
+}
+
+#[tokio::test]
+async fn test_sync_missing_source() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+	let local = local_tree("missing", &[]);
+
+	let options = abbrws::SyncOptions { delete: true, utc_offset_s: None };
+	let result = client.plan_sync(&local, "$HOME/deploy", abbrws::SyncDirection::Upload, &options).await;
+	assert!(let Err(abbrws::Error::Io(_)) = result);
+
+	let result = client.plan_sync(&local, "$HOME/missing", abbrws::SyncDirection::Download, &options).await;
+	assert!(let Err(abbrws::Error::FileService(abbrws::FileServiceError { kind: abbrws::FileServiceErrorKind::NotFound, .. })) = result);
+}
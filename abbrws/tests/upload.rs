@@ -0,0 +1,68 @@
+use assert2::assert;
+use hyper::StatusCode;
+
+mod mock;
+use mock::MockServer;
+
+/// Authenticate requests like the controller does.
+fn respond(request: &mock::RecordedRequest) -> hyper::Response<hyper::Body> {
+	if !mock::check_digest(request) {
+		return mock::challenge();
+	}
+	match request.path.as_str() {
+		"/fileservice/" => mock::json(StatusCode::OK, r#"{"_embedded": {"_state": []}}"#),
+		"/fileservice/$HOME/backup.tar/" => mock::empty(StatusCode::CREATED),
+		_ => mock::empty(StatusCode::NOT_FOUND),
+	}
+}
+
+#[tokio::test]
+async fn test_upload_file_from() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let data = mock::contents();
+	let mut progress = Vec::new();
+	let content_type : abbrws::Mime = "application/x-tar".parse().unwrap();
+	client.upload_file_from("$HOME/backup.tar", content_type, &data[..], Some(data.len() as u64), |x| progress.push(x)).await.unwrap();
+
+	// The body is sent exactly once, after the authentication handshake.
+	let requests = server.requests();
+	let uploads : Vec<_> = requests.iter().filter(|x| x.path == "/fileservice/$HOME/backup.tar/").collect();
+	assert!(uploads.len() == 1);
+	assert!(mock::check_digest(uploads[0]));
+	assert!(uploads[0].headers[hyper::header::CONTENT_TYPE] == "application/x-tar");
+	assert!(uploads[0].headers[hyper::header::CONTENT_LENGTH] == "1000000");
+	assert!(uploads[0].body == data);
+	assert!(requests.iter().filter(|x| !x.body.is_empty()).count() == 1);
+
+	let last = progress.last().copied().unwrap();
+	assert!(last == abbrws::Progress { transferred: 1_000_000, total: Some(1_000_000) });
+}
+
+#[tokio::test]
+async fn test_upload_file_from_path() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let local_path = std::env::temp_dir().join(format!("abbrws-upload-test-{}", std::process::id()));
+	std::fs::write(&local_path, mock::contents()).unwrap();
+	let result = client.upload_file_from_path(&local_path, "$HOME/backup.tar", mime::APPLICATION_OCTET_STREAM, |_| ()).await;
+	std::fs::remove_file(&local_path).unwrap();
+	assert!(let Ok(()) = result);
+
+	let requests = server.requests();
+	let upload = requests.iter().find(|x| x.path == "/fileservice/$HOME/backup.tar/").unwrap();
+	assert!(upload.headers[hyper::header::CONTENT_LENGTH] == "1000000");
+	assert!(upload.body == mock::contents());
+}
+
+#[tokio::test]
+async fn test_upload_file_from_error() {
+	let server = MockServer::start(respond);
+	let mut client = server.client();
+
+	let data = mock::contents();
+	let result = client.upload_file_from("$HOME/missing/backup.tar", mime::APPLICATION_OCTET_STREAM, &data[..], None, |_| ()).await;
+	assert!(let Err(abbrws::Error::FileService(abbrws::FileServiceError { kind: abbrws::FileServiceErrorKind::NotFound, .. })) = result);
+}